@@ -0,0 +1,167 @@
+// ausgabe.rs - Maschinenlesbare Ausgabe (JSON) der Abfrageergebnisse
+use anyhow::{Result, bail};
+use serde::Serialize;
+use crate::befehlsbibliothek::GespeicherterBefehl;
+use crate::csv_data::CsvData;
+use crate::csv_parser::{Eintrag, Spaltennummern};
+
+/// Version des JSON-Dokuments; wird bei inkompatiblen Änderungen erhöht.
+///
+/// Version 1: jedes Dokument hat `format_version` und ist eines von
+/// - `PaarErgebnis` (`lookup`, `command`, Assistent): `erste_spalte`, `zweite_spalte`,
+///   `spaltennummern`, `ausgewaehlte_zeilen`, `csv_zeilen` (je `datenzeile`, `erste_spalte`,
+///   `zweite_spalte`, `spaltennummern`) und `kommando`
+/// - `EintragListe` (`list-first`, `list-second`): `erste_spalte` und `eintraege`
+/// - `BefehlListe` (`bibliothek list`): `befehle`
+///
+/// Zeilennummern (`datenzeile`, `ausgewaehlte_zeilen`) zählen die Datenzeilen der geladenen
+/// Tabelle ab 1, wie Zeilenangaben. Leere und übersprungene Zeilen der Datei zählen nicht mit;
+/// die Warnungen beim Laden nennen dagegen die Zeile in der Datei (`Dateizeile`).
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Ausgabeformat für Abfrageergebnisse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ausgabeformat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Ausgabeformat {
+    pub fn parse(text: &str) -> Result<Self> {
+        match text {
+            "text" => Ok(Ausgabeformat::Text),
+            "json" => Ok(Ausgabeformat::Json),
+            other => bail!("Unbekanntes Ausgabeformat '{}' (erlaubt: text, json)", other),
+        }
+    }
+}
+
+/// Eine CSV-Zeile mit Nummer und geparsten Spalten
+#[derive(Debug, Serialize)]
+pub struct CsvZeileJson<'a> {
+    /// 1-basierte Nummer der Datenzeile, wie in Zeilenangaben (nicht die Zeile in der Datei)
+    pub datenzeile: usize,
+    pub erste_spalte: &'a Eintrag,
+    pub zweite_spalte: &'a [Eintrag],
+    pub spaltennummern: &'a Spaltennummern,
+}
+
+/// Ergebnis zu einem Paar: Auswahl, passende Zeilen und optional der Befehl
+#[derive(Debug, Serialize)]
+pub struct PaarErgebnis<'a> {
+    pub format_version: u32,
+    pub erste_spalte: &'a str,
+    pub zweite_spalte: &'a str,
+    pub spaltennummern: Vec<u32>,
+    /// `null`, wenn keine Zeilenangabe gemacht wurde
+    pub ausgewaehlte_zeilen: Option<Vec<i32>>,
+    pub csv_zeilen: Vec<CsvZeileJson<'a>>,
+    pub kommando: Option<String>,
+}
+
+impl<'a> PaarErgebnis<'a> {
+    /// Sammelt alle Zeilen des Paares, eingeschränkt auf die ausgewählten Zeilen
+    pub fn new(
+        csv_data: &'a CsvData,
+        first: &'a str,
+        second: &'a str,
+        ausgewaehlte_zeilen: Option<&[i32]>,
+        kommando: Option<String>,
+    ) -> Self {
+        let csv_zeilen = csv_data
+            .get_row_numbers_for_pair(first, second)
+            .into_iter()
+            .filter(|row_num| ausgewaehlte_zeilen.is_none_or(|rows| rows.contains(row_num)))
+            .map(|row_num| {
+                let (first_entry, second_entries, numbers) = &csv_data.raw_data[(row_num - 1) as usize];
+                CsvZeileJson {
+                    datenzeile: row_num as usize,
+                    erste_spalte: first_entry,
+                    zweite_spalte: second_entries,
+                    spaltennummern: numbers,
+                }
+            })
+            .collect();
+        
+        Self {
+            format_version: FORMAT_VERSION,
+            erste_spalte: first,
+            zweite_spalte: second,
+            spaltennummern: csv_data.find_column_numbers_for_pair(first, second),
+            ausgewaehlte_zeilen: ausgewaehlte_zeilen.map(<[i32]>::to_vec),
+            csv_zeilen,
+            kommando,
+        }
+    }
+}
+
+/// Liste der ersten Spalten (`list-first`) oder der zweiten Spalten zu einer ersten (`list-second`)
+#[derive(Debug, Serialize)]
+pub struct EintragListe<'a> {
+    pub format_version: u32,
+    /// `null` bei der Liste der ersten Spalten
+    pub erste_spalte: Option<&'a str>,
+    pub eintraege: &'a [Eintrag],
+}
+
+impl<'a> EintragListe<'a> {
+    pub fn new(erste_spalte: Option<&'a str>, eintraege: &'a [Eintrag]) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            erste_spalte,
+            eintraege,
+        }
+    }
+}
+
+/// Die gespeicherten Befehle der Befehlsbibliothek (`bibliothek list`)
+#[derive(Debug, Serialize)]
+pub struct BefehlListe<'a> {
+    pub format_version: u32,
+    pub befehle: &'a [GespeicherterBefehl],
+}
+
+impl<'a> BefehlListe<'a> {
+    pub fn new(befehle: &'a [GespeicherterBefehl]) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            befehle,
+        }
+    }
+}
+
+/// Formatiert einen Wert als eingerücktes JSON-Dokument
+pub fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_parser::{CsvParser, CSV_DATA};
+    
+    #[test]
+    fn test_paar_ergebnis_json() {
+        let csv_data = CsvData::from_tabelle(CsvParser::parse_csv_text(CSV_DATA).0);
+        let first = &csv_data.all_first_columns[0].name;
+        let second = &csv_data.get_seconds_for_first(first).unwrap()[0].name;
+        let rows = csv_data.get_row_numbers_for_pair(first, second);
+        
+        let ergebnis = PaarErgebnis::new(&csv_data, first, second, Some(&rows[..1]), Some("rpnn".to_string()));
+        let json = serde_json::to_value(&ergebnis).unwrap();
+        
+        assert_eq!(json["format_version"], FORMAT_VERSION);
+        assert_eq!(json["erste_spalte"], first.as_str());
+        assert_eq!(json["ausgewaehlte_zeilen"], serde_json::json!([rows[0]]));
+        assert_eq!(json["csv_zeilen"].as_array().unwrap().len(), 1);
+        assert_eq!(json["csv_zeilen"][0]["datenzeile"], rows[0]);
+        assert!(json["csv_zeilen"][0]["spaltennummern"]["art"].is_string());
+        assert_eq!(json["kommando"], "rpnn");
+        
+        let liste = serde_json::to_value(EintragListe::new(None, &csv_data.all_first_columns)).unwrap();
+        assert_eq!(liste["format_version"], FORMAT_VERSION);
+        assert!(liste["erste_spalte"].is_null());
+        assert_eq!(liste["eintraege"][0]["name"], first.as_str());
+    }
+}
@@ -1,34 +1,192 @@
 use inquire::{autocompletion::Replacement, Autocomplete};
+use std::cmp::Reverse;
 use std::error::Error;
+use crate::csv_parser::Eintrag;
+use crate::normalisierung::normalize;
 
-// Einfache Autocomplete-Implementierung
+// Trennt den Alias-Hinweis vom kanonischen Namen in einem Vorschlag
+const ALIAS_HINWEIS: &str = "  ← ";
+
+/// Entfernt einen angehängten Alias-Hinweis aus einem Vorschlag
+pub fn strip_alias_hint(text: &str) -> &str {
+    text.split_once(ALIAS_HINWEIS).map_or(text, |(name, _)| name)
+}
+
+/// Standardanzahl angezeigter Vorschläge
+pub const DEFAULT_LIMIT: usize = 25;
+
+// Bewertung eines Treffers: höher ist besser
+const SCORE_EXAKT: u32 = 1000;
+const SCORE_PRAEFIX: u32 = 800;
+const SCORE_WORTANFANG: u32 = 600;
+const SCORE_TEILSTRING: u32 = 400;
+const SCORE_TEILFOLGE: u32 = 200;
+const SCORE_TIPPFEHLER: u32 = 100;
+
+/// Bewertet, wie gut die normalisierte Eingabe zu einem normalisierten Kandidaten passt:
+/// exakt > Präfix > Wortanfang > Teilstring > Teilfolge > Tippfehler (Editierdistanz)
+pub fn score_match(input_lower: &str, candidate_lower: &str) -> Option<u32> {
+    if candidate_lower == input_lower {
+        return Some(SCORE_EXAKT);
+    }
+    if candidate_lower.starts_with(input_lower) {
+        return Some(SCORE_PRAEFIX);
+    }
+    if let Some(pos) = candidate_lower.find(input_lower) {
+        // Treffer direkt nach einem Trennzeichen zählt als Wortanfang
+        let mut positions = candidate_lower.match_indices(input_lower).map(|(i, _)| i);
+        if positions.any(|i| is_word_boundary(candidate_lower, i)) {
+            return Some(SCORE_WORTANFANG);
+        }
+        // Frühere Treffer sind etwas besser
+        return Some(SCORE_TEILSTRING - (pos.min(99) as u32));
+    }
+    if let Some(gaps) = subsequence_gaps(input_lower, candidate_lower) {
+        return Some(SCORE_TEILFOLGE - (gaps.min(99) as u32));
+    }
+    
+    // Tippfehler: Vergleich mit dem gleich langen Anfang des Kandidaten
+    let input_len = input_lower.chars().count();
+    if input_len >= 3 {
+        let prefix: String = candidate_lower.chars().take(input_len).collect();
+        let distance = edit_distance(input_lower, &prefix);
+        let erlaubt = if input_len <= 5 { 1 } else { 2 };
+        if distance <= erlaubt {
+            return Some(SCORE_TIPPFEHLER - (distance as u32) * 10);
+        }
+    }
+    
+    None
+}
+
+// Wortanfang: Textbeginn oder nach einem Trennzeichen
+fn is_word_boundary(text: &str, byte_pos: usize) -> bool {
+    byte_pos == 0
+        || text[..byte_pos]
+            .chars()
+            .next_back()
+            .is_some_and(|c| matches!(c, '_' | '-' | ' ' | '(' | '/' | '.'))
+}
+
+// Anzahl übersprungener Zeichen, wenn alle Eingabezeichen der Reihe nach vorkommen
+fn subsequence_gaps(input: &str, candidate: &str) -> Option<usize> {
+    let mut gaps = 0;
+    let mut candidate_chars = candidate.chars();
+    
+    for c in input.chars() {
+        loop {
+            match candidate_chars.next() {
+                Some(cc) if cc == c => break,
+                Some(_) => gaps += 1,
+                None => return None,
+            }
+        }
+    }
+    
+    Some(gaps)
+}
+
+// Levenshtein-Distanz mit Vertauschung benachbarter Zeichen
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dist = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    
+    dist[a.len()][b.len()]
+}
+
+/// Autocomplete mit bewerteten Vorschlägen: ein Vorschlag pro kanonischem Namen
 #[derive(Clone)]
 pub struct SimpleAutocomplete {
-    items: Vec<String>,
+    items: Vec<Eintrag>,
+    // Normalisierte Schreibweisen je Eintrag (Name zuerst, dann Aliase)
+    normalized: Vec<Vec<String>>,
+    limit: usize,
 }
 
 impl SimpleAutocomplete {
-    pub fn new(items: Vec<String>) -> Self {
-        Self { items }
+    pub fn new(items: Vec<Eintrag>) -> Self {
+        let normalized = items
+            .iter()
+            .map(|item| item.alle_namen().map(|name| normalize(name)).collect())
+            .collect();
+        
+        Self { items, normalized, limit: DEFAULT_LIMIT }
+    }
+    
+    /// Setzt die maximale Anzahl angezeigter Vorschläge
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    
+    // Bester Treffer eines Eintrags: (Bewertung, passender Alias falls nicht der Name)
+    fn best_match<'a>(item: &'a Eintrag, normalized: &[String], input_norm: &str) -> Option<(u32, Option<&'a String>)> {
+        let name_score = score_match(input_norm, &normalized[0]).map(|score| (score, None));
+        let alias_score = item.aliase
+            .iter()
+            .zip(&normalized[1..])
+            .filter_map(|(alias, alias_norm)| score_match(input_norm, alias_norm).map(|score| (score, Some(alias))))
+            .max_by_key(|(score, _)| *score);
+        
+        // Bei gleicher Bewertung gewinnt der kanonische Name
+        match (name_score, alias_score) {
+            (Some(name), Some(alias)) if alias.0 > name.0 => Some(alias),
+            (Some(name), _) => Some(name),
+            (None, alias) => alias,
+        }
     }
 }
 
 impl Autocomplete for SimpleAutocomplete {
     fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
-        let input_lower = input.trim().to_lowercase();
+        let input_norm = normalize(input.trim());
         
-        if input_lower.is_empty() {
-            return Ok(self.items.iter().take(25).cloned().collect());
+        if input_norm.is_empty() {
+            return Ok(self.items.iter().take(self.limit).map(|item| item.name.clone()).collect());
         }
         
-        let filtered: Vec<String> = self.items
+        let mut scored: Vec<(u32, &Eintrag, Option<&String>)> = self.items
             .iter()
-            .filter(|item| item.to_lowercase().contains(&input_lower))
-            .take(25)
-            .cloned()
+            .zip(&self.normalized)
+            .filter_map(|(item, normalized)| {
+                Self::best_match(item, normalized, &input_norm).map(|(score, alias)| (score, item, alias))
+            })
+            .collect();
+        
+        // Stabil sortieren: bei gleicher Bewertung bleibt die alphabetische Reihenfolge
+        scored.sort_by_key(|(score, _, _)| Reverse(*score));
+        
+        // Passt nur ein Alias, wird er als Hinweis hinter dem Namen angezeigt
+        let suggestions = scored
+            .into_iter()
+            .take(self.limit)
+            .map(|(_, item, alias)| match alias {
+                Some(alias) => format!("{}{}{}", item.name, ALIAS_HINWEIS, alias),
+                None => item.name.clone(),
+            })
             .collect();
         
-        Ok(filtered)
+        Ok(suggestions)
     }
 
     fn get_completion(
@@ -37,7 +195,59 @@ impl Autocomplete for SimpleAutocomplete {
         highlighted_suggestion: Option<String>,
     ) -> Result<Replacement, Box<dyn Error + Send + Sync>> {
         Ok(highlighted_suggestion
-            .map(Replacement::Some)
+            .map(|suggestion| Replacement::Some(strip_alias_hint(&suggestion).to_string()))
             .unwrap_or(Replacement::None))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn eintrag(name: &str, aliase: &[&str]) -> Eintrag {
+        Eintrag {
+            name: name.to_string(),
+            aliase: aliase.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+    
+    #[test]
+    fn test_score_order() {
+        let score = |input: &str, candidate: &str| score_match(&normalize(input), &normalize(candidate));
+        let exakt = score("religion", "Religion").unwrap();
+        let praefix = score("rel", "Religionen").unwrap();
+        let wortanfang = score("rel", "Meta_Religion").unwrap();
+        let teilstring = score("lig", "Religionen").unwrap();
+        let teilfolge = score("rlgn", "Religionen").unwrap();
+        let tippfehler = score("relgiion", "Religionen").unwrap();
+        
+        assert!(exakt > praefix);
+        assert!(praefix > wortanfang);
+        assert!(wortanfang > teilstring);
+        assert!(teilstring > teilfolge);
+        assert!(teilfolge > tippfehler);
+        assert_eq!(score("xyz", "Religionen"), None);
+        assert_eq!(score("Superkraefte", "Superkräfte"), Some(exakt));
+    }
+    
+    #[test]
+    fn test_suggestions_are_ranked_and_limited() {
+        let mut autocomplete = SimpleAutocomplete::new(vec![
+            eintrag("Areligion", &[]),
+            eintrag("Galaxie", &["kreis"]),
+            eintrag("Religionen", &["religion"]),
+        ]);
+        
+        let suggestions = autocomplete.get_suggestions("relgion").unwrap();
+        assert_eq!(suggestions[0], "Religionen");
+        
+        let suggestions = autocomplete.get_suggestions("kries").unwrap();
+        assert_eq!(suggestions, vec!["Galaxie  ← kreis"]);
+        
+        let suggestions = autocomplete.get_suggestions("rel").unwrap();
+        assert_eq!(suggestions, vec!["Religionen", "Areligion"]);
+        
+        let mut limited = autocomplete.with_limit(1);
+        assert_eq!(limited.get_suggestions("").unwrap(), vec!["Areligion"]);
+    }
+}
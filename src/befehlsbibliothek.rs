@@ -0,0 +1,126 @@
+// befehlsbibliothek.rs - Benannte, gespeicherte rpnn-Befehle in einer JSON-Datei
+//
+// Jeder Eintrag enthält Programm und Argumente einzeln, damit er ohne Shell erneut
+// ausgeführt werden kann, und zusätzlich die gequotete Anzeigeform.
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use crate::ausgabe;
+
+/// Umgebungsvariable mit dem Pfad zur Bibliotheksdatei
+pub const BIBLIOTHEK_ENV_VAR: &str = "RPNN_BEFEHLE";
+/// Dateiname unter `~/.config/rpnn/` (ohne HOME im aktuellen Verzeichnis)
+pub const BIBLIOTHEK_DATEI: &str = "befehle.json";
+
+/// Ein gespeicherter Befehl
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GespeicherterBefehl {
+    pub name: String,
+    /// Anzeigeform, für eine POSIX-Shell gequotet
+    pub befehl: String,
+    pub programm: String,
+    pub args: Vec<String>,
+    /// Zeitpunkt des Speicherns, z.B. `2024-05-01 12:00:00`
+    pub gespeichert: String,
+}
+
+/// Alle gespeicherten Befehle in der Reihenfolge des Speicherns
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Befehlsbibliothek {
+    befehle: Vec<GespeicherterBefehl>,
+}
+
+impl Befehlsbibliothek {
+    /// `RPNN_BEFEHLE`, sonst `~/.config/rpnn/befehle.json`
+    pub fn standard_pfad() -> PathBuf {
+        if let Some(path) = std::env::var(BIBLIOTHEK_ENV_VAR).ok().filter(|p| !p.trim().is_empty()) {
+            return PathBuf::from(path);
+        }
+        match std::env::var_os("HOME") {
+            Some(home) => Path::new(&home).join(".config/rpnn").join(BIBLIOTHEK_DATEI),
+            None => PathBuf::from(BIBLIOTHEK_DATEI),
+        }
+    }
+    
+    /// Liest die Bibliothek; eine fehlende Datei ergibt eine leere Bibliothek
+    pub fn laden(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Befehlsbibliothek '{}' konnte nicht gelesen werden", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Ungültige Befehlsbibliothek '{}'", path.display()))
+    }
+    
+    /// Schreibt die Bibliothek (legt fehlende Verzeichnisse an)
+    pub fn speichern(&self, path: &Path) -> Result<()> {
+        if let Some(verzeichnis) = path.parent().filter(|v| !v.as_os_str().is_empty()) {
+            std::fs::create_dir_all(verzeichnis)
+                .with_context(|| format!("Verzeichnis '{}' konnte nicht angelegt werden", verzeichnis.display()))?;
+        }
+        std::fs::write(path, ausgabe::to_json(self)?)
+            .with_context(|| format!("Befehlsbibliothek '{}' konnte nicht geschrieben werden", path.display()))
+    }
+    
+    pub fn befehle(&self) -> &[GespeicherterBefehl] {
+        &self.befehle
+    }
+    
+    pub fn finden(&self, name: &str) -> Option<&GespeicherterBefehl> {
+        self.befehle.iter().find(|b| b.name == name)
+    }
+    
+    /// Fügt einen Befehl hinzu; Namen müssen eindeutig und nicht leer sein
+    pub fn hinzufuegen(&mut self, befehl: GespeicherterBefehl) -> Result<()> {
+        if befehl.name.trim().is_empty() {
+            bail!("Der Name eines gespeicherten Befehls darf nicht leer sein");
+        }
+        if self.finden(&befehl.name).is_some() {
+            bail!("Es gibt bereits einen gespeicherten Befehl '{}'", befehl.name);
+        }
+        self.befehle.push(befehl);
+        Ok(())
+    }
+    
+    /// Entfernt den Befehl mit diesem Namen und liefert ihn zurück
+    pub fn entfernen(&mut self, name: &str) -> Option<GespeicherterBefehl> {
+        let index = self.befehle.iter().position(|b| b.name == name)?;
+        Some(self.befehle.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn befehl(name: &str) -> GespeicherterBefehl {
+        GespeicherterBefehl {
+            name: name.to_string(),
+            befehl: "mein-rpnn --vorhervonausschnitt 1-5 --spaltenname 'a b' c".to_string(),
+            programm: "mein-rpnn".to_string(),
+            args: vec!["--vorhervonausschnitt".into(), "1-5".into(), "--spaltenname".into(), "a b".into(), "c".into()],
+            gespeichert: "2024-05-01 12:00:00".to_string(),
+        }
+    }
+    
+    #[test]
+    fn test_bibliothek_speichern_und_laden() {
+        let path = std::env::temp_dir().join(format!("rpnn-befehle-{}.json", std::process::id()));
+        let mut bibliothek = Befehlsbibliothek::laden(&path).unwrap();
+        assert!(bibliothek.befehle().is_empty());
+        
+        bibliothek.hinzufuegen(befehl("religion")).unwrap();
+        bibliothek.hinzufuegen(befehl("planet")).unwrap();
+        assert!(bibliothek.hinzufuegen(befehl("planet")).is_err());
+        assert!(bibliothek.hinzufuegen(befehl(" ")).is_err());
+        bibliothek.speichern(&path).unwrap();
+        
+        let mut geladen = Befehlsbibliothek::laden(&path).unwrap();
+        assert_eq!(geladen.finden("religion"), Some(&befehl("religion")));
+        assert_eq!(geladen.entfernen("religion").map(|b| b.name), Some("religion".to_string()));
+        assert_eq!(geladen.befehle().len(), 1);
+        assert!(geladen.entfernen("religion").is_none());
+        
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,550 @@
+// cli.rs - Nicht-interaktiver Kommandozeilen-Modus
+use std::path::Path;
+use anyhow::{Result, anyhow, bail};
+use rpnn::ausgabe::{self, Ausgabeformat, BefehlListe, EintragListe, PaarErgebnis};
+use rpnn::befehlsbibliothek::Befehlsbibliothek;
+use rpnn::csv_data::{Aufloesung, CsvData, format_csv_row};
+use rpnn::csv_parser::CsvQuelle;
+use rpnn::kommando::{self, KommandoOptionen, SpaltenAuswahl, format_zeilenangabe_fuer_kommando};
+use rpnn::konfiguration::{Konfiguration, finde_im_path};
+use rpnn::zeilen_parser::{Segment, ZeilenParser, Zeilenangabe};
+use rpnn::zeilen_set::ZeilenSet;
+use serde::Serialize;
+
+const HILFE: &str = "\
+Verwendung:
+  rpnn [--csv <pfad>] [--format text|json] [--vorschlaege <n>] [KOMMANDO-OPTIONEN] [UNTERKOMMANDO]
+
+  rpnn                                          Interaktiver Assistent
+  rpnn list-first                               Alle ersten Spalten auflisten
+  rpnn list-second <erste>                      Zweite Spalten zu einer ersten Spalte auflisten
+  rpnn lookup <erste> <zweite> [--zeilen <Z>]   CSV-Zeilen zu einem Paar anzeigen
+  rpnn command <erste> <zweite> <zeilen>        rpnn-Befehl für ein Paar erzeugen
+       [--nummern]                              Spalten über ihre Nummern statt den Namen wählen
+  rpnn bibliothek list                          Gespeicherte Befehle auflisten
+  rpnn bibliothek run <name>                    Gespeicherten Befehl ausführen
+  rpnn bibliothek delete <name>                 Gespeicherten Befehl löschen
+  rpnn help                                     Diese Hilfe anzeigen
+
+Ohne --csv wird die Umgebungsvariable RPNN_CSV, sonst die eingebettete Tabelle verwendet.
+--format json gibt die Ergebnisse als versioniertes JSON-Dokument auf stdout aus; alle übrigen
+Texte (auch die des Assistenten) gehen dann auf stderr.
+--vorschlaege begrenzt die Autocomplete-Vorschläge im Assistenten (Standard: 25).
+Die Befehlsbibliothek liegt in RPNN_BEFEHLE, sonst in ~/.config/rpnn/befehle.json; Befehle werden
+im Assistenten gespeichert. bibliothek run gibt die Ausgabe von rpnn unverändert weiter.
+
+Kommando-Optionen (überschreiben die Konfigurationsdatei):
+  --config <pfad>            Konfigurationsdatei (sonst RPNN_CONFIG, ./rpnn.json, ~/.config/rpnn/rpnn.json)
+  --rpnn <pfad>              rpnn-Programm (sonst aus der Konfiguration, dem PATH oder ../target/debug/mein-rpnn)
+  --zeilen-flag <flag>       Flag vor der Zeilenangabe (Standard: --vorhervonausschnitt)
+  --spaltenname-flag <flag>  Flag vor den Spaltennamen (Standard: --spaltenname)
+  --spalten-flag <flag>      Flag für Spaltennummern (Standard: --spalten)
+  --rpnn-arg <arg>           Zusätzliches Argument für jeden Aufruf (mehrfach möglich)";
+
+// Führt ein Unterkommando aus (args ohne Programmnamen)
+pub fn run(args: &[String], quelle: &CsvQuelle, kommando_args: &KommandoArgs, format: Ausgabeformat) -> Result<()> {
+    let (subcommand, rest) = match args.split_first() {
+        Some((subcommand, rest)) => (subcommand.as_str(), rest),
+        None => bail!("Kein Unterkommando angegeben\n\n{}", HILFE),
+    };
+
+    match subcommand {
+        "help" | "--help" | "-h" => {
+            println!("{}", HILFE);
+            Ok(())
+        }
+        "list-first" => {
+            expect_positionals(rest, 0, "list-first")?;
+            list_first(&load_csv_data(quelle)?, format)
+        }
+        "list-second" => {
+            let positionals = expect_positionals(rest, 1, "list-second <erste>")?;
+            list_second(&load_csv_data(quelle)?, &positionals[0], format)
+        }
+        "lookup" => {
+            let (zeilen, rest) = take_option(rest, "--zeilen")?;
+            let positionals = expect_positionals(&rest, 2, "lookup <erste> <zweite> [--zeilen <Z>]")?;
+            lookup(&load_csv_data(quelle)?, &positionals[0], &positionals[1], zeilen.as_deref(), format)
+        }
+        "command" => {
+            let (nach_nummern, rest) = take_flag(rest, "--nummern");
+            let positionals = expect_positionals(&rest, 3, "command <erste> <zweite> <zeilen> [--nummern]")?;
+            let optionen = kommando_args.optionen()?;
+            let csv_data = load_csv_data(quelle)?;
+            command(&csv_data, &positionals[0], &positionals[1], &positionals[2], nach_nummern, &optionen, format)
+        }
+        "bibliothek" => bibliothek(rest, &Befehlsbibliothek::standard_pfad(), format),
+        other => bail!("Unbekanntes Unterkommando '{}'\n\n{}", other, HILFE),
+    }
+}
+
+// Gespeicherte Befehle in der Bibliothek unter `path` auflisten, ausführen oder löschen
+fn bibliothek(args: &[String], path: &Path, format: Ausgabeformat) -> Result<()> {
+    const VERWENDUNG: &str = "bibliothek list|run <name>|delete <name>";
+
+    let mut bibliothek = Befehlsbibliothek::laden(path)?;
+
+    let (aktion, rest) = match args.split_first() {
+        Some((aktion, rest)) => (aktion.as_str(), rest),
+        None => bail!("Keine Aktion angegeben\nVerwendung: rpnn {}", VERWENDUNG),
+    };
+
+    match aktion {
+        "list" => {
+            expect_positionals(rest, 0, VERWENDUNG)?;
+            if format == Ausgabeformat::Json {
+                return print_json(&BefehlListe::new(bibliothek.befehle()));
+            }
+            eprintln!("📚 Befehlsbibliothek: {} ({} Befehle)", path.display(), bibliothek.befehle().len());
+            for befehl in bibliothek.befehle() {
+                println!("{}\t{}\t{}", befehl.name, befehl.gespeichert, befehl.befehl);
+            }
+            Ok(())
+        }
+        "run" => {
+            let name = &expect_positionals(rest, 1, VERWENDUNG)?[0];
+            let befehl = bibliothek
+                .finden(name)
+                .ok_or_else(|| anyhow!("Kein gespeicherter Befehl '{}' in {}", name, path.display()))?;
+
+            eprintln!("▶️  {}", befehl.befehl);
+            let ausfuehrung = kommando::ausfuehren(&befehl.programm, &befehl.args)?;
+            print!("{}", ausfuehrung.stdout);
+            eprint!("{}", ausfuehrung.stderr);
+            if !ausfuehrung.status.success() {
+                bail!("{}: {}", befehl.programm, ausfuehrung.zusammenfassung());
+            }
+            Ok(())
+        }
+        "delete" => {
+            let name = &expect_positionals(rest, 1, VERWENDUNG)?[0];
+            if bibliothek.entfernen(name).is_none() {
+                bail!("Kein gespeicherter Befehl '{}' in {}", name, path.display());
+            }
+            bibliothek.speichern(path)?;
+            eprintln!("🗑️  '{}' gelöscht", name);
+            Ok(())
+        }
+        other => bail!("Unbekannte Aktion '{}'\nVerwendung: rpnn {}", other, VERWENDUNG),
+    }
+}
+
+// Lädt die Tabelle; Quelle und Warnungen gehen auf stderr, damit stdout maschinenlesbar bleibt
+fn load_csv_data(quelle: &CsvQuelle) -> Result<CsvData> {
+    let csv_data = CsvData::load(quelle)?;
+
+    for warnung in &csv_data.warnungen {
+        eprintln!("⚠️  {}", warnung);
+    }
+    eprintln!("📂 CSV-Quelle: {} ({} CSV-Zeilen)", quelle, csv_data.raw_data.len());
+
+    Ok(csv_data)
+}
+
+// Gibt einen Wert als JSON-Dokument auf stdout aus
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", ausgabe::to_json(value)?);
+    Ok(())
+}
+
+// Entfernt eine Option mit Wert (`--name wert` oder `--name=wert`) aus den Argumenten
+pub fn take_option(args: &[String], name: &str) -> Result<(Option<String>, Vec<String>)> {
+    let prefix = format!("{}=", name);
+    let mut value = None;
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            let v = iter.next().ok_or_else(|| anyhow!("Option {} erwartet einen Wert", name))?;
+            value = Some(v.clone());
+        } else if let Some(v) = arg.strip_prefix(&prefix) {
+            value = Some(v.to_string());
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    Ok((value, rest))
+}
+
+// Entfernt eine wiederholbare Option mit Wert aus den Argumenten und liefert alle Werte
+fn take_options(args: &[String], name: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let prefix = format!("{}=", name);
+    let mut values = Vec::new();
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            let v = iter.next().ok_or_else(|| anyhow!("Option {} erwartet einen Wert", name))?;
+            values.push(v.clone());
+        } else if let Some(v) = arg.strip_prefix(&prefix) {
+            values.push(v.to_string());
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    Ok((values, rest))
+}
+
+// Kommando-Optionen von der Kommandozeile. Die Konfigurationsdatei wird erst in `optionen` gelesen,
+// also nur dort, wo ein Befehl gebaut wird; `help`, `list-*` und `lookup` stört eine fehlerhafte
+// `rpnn.json` daher nicht
+#[derive(Debug, Default)]
+pub struct KommandoArgs {
+    config_pfad: Option<String>,
+    programm: Option<String>,
+    zeilen_flag: Option<String>,
+    spaltenname_flag: Option<String>,
+    spalten_flag: Option<String>,
+    zusatz_args: Vec<String>,
+}
+
+impl KommandoArgs {
+    // Standardwerte, dann Konfigurationsdatei, dann Kommandozeile; ohne konfiguriertes Programm
+    // wird rpnn im PATH gesucht
+    pub fn optionen(&self) -> Result<KommandoOptionen> {
+        let mut optionen = KommandoOptionen::default();
+        let mut programm_gesetzt = false;
+
+        if let Some(pfad) = Konfiguration::finde_datei(self.config_pfad.clone()) {
+            let konfiguration = Konfiguration::laden(&pfad)?;
+            programm_gesetzt = konfiguration.kommando.programm.is_some();
+            konfiguration.anwenden(&mut optionen);
+        }
+
+        if let Some(programm) = &self.programm {
+            optionen.programm = programm.clone();
+            programm_gesetzt = true;
+        }
+
+        let felder = [
+            (&self.zeilen_flag, &mut optionen.zeilen_flag),
+            (&self.spaltenname_flag, &mut optionen.spaltenname_flag),
+            (&self.spalten_flag, &mut optionen.spalten_flag),
+        ];
+        for (wert, feld) in felder {
+            if let Some(wert) = wert {
+                *feld = wert.clone();
+            }
+        }
+
+        if !self.zusatz_args.is_empty() {
+            optionen.zusatz_args = self.zusatz_args.clone();
+        }
+
+        if !programm_gesetzt && let Some(gefunden) = finde_im_path(&optionen.programm_name) {
+            optionen.programm = gefunden.display().to_string();
+        }
+
+        Ok(optionen)
+    }
+}
+
+// Entfernt die Kommando-Optionen aus den Argumenten, ohne die Konfigurationsdatei zu lesen
+pub fn take_kommando_args(args: &[String]) -> Result<(KommandoArgs, Vec<String>)> {
+    let (config_pfad, args) = take_option(args, "--config")?;
+    let (programm, args) = take_option(&args, "--rpnn")?;
+    let (zeilen_flag, args) = take_option(&args, "--zeilen-flag")?;
+    let (spaltenname_flag, args) = take_option(&args, "--spaltenname-flag")?;
+    let (spalten_flag, args) = take_option(&args, "--spalten-flag")?;
+    let (zusatz_args, args) = take_options(&args, "--rpnn-arg")?;
+
+    let kommando_args = KommandoArgs {
+        config_pfad,
+        programm,
+        zeilen_flag,
+        spaltenname_flag,
+        spalten_flag,
+        zusatz_args,
+    };
+    Ok((kommando_args, args))
+}
+
+// Entfernt einen Schalter ohne Wert aus den Argumenten
+fn take_flag(args: &[String], name: &str) -> (bool, Vec<String>) {
+    let rest: Vec<String> = args.iter().filter(|a| *a != name).cloned().collect();
+    (rest.len() != args.len(), rest)
+}
+
+// Prüft die Anzahl der Positionsargumente und lehnt unbekannte Optionen ab
+fn expect_positionals<'a>(args: &'a [String], count: usize, usage: &str) -> Result<&'a [String]> {
+    if let Some(option) = args.iter().find(|a| a.starts_with("--")) {
+        bail!("Unbekannte Option '{}'\nVerwendung: rpnn {}", option, usage);
+    }
+    if args.len() != count {
+        bail!("Erwartet {} Argument(e), erhalten {}\nVerwendung: rpnn {}", count, args.len(), usage);
+    }
+    Ok(args)
+}
+
+// Validiert eine Zeilenangabe und liefert die existierenden Zeilennummern sowie ihre `v`-Segmente
+// und Brüche, die im Befehl erhalten bleiben
+fn parse_zeilen(input: &str, csv_data: &CsvData) -> Result<(ZeilenSet, Vec<Segment>)> {
+    let angabe = Zeilenangabe::parse(input)
+        .map_err(|fehler| anyhow!("Ungültige Zeilenangabe:\n{}", fehler.anzeigen(input)))?;
+    let max_zeile = csv_data.raw_data.len() as i32;
+    for (segment, zeilen) in ZeilenParser::aufloesungen(&angabe, max_zeile)? {
+        if zeilen.is_empty() {
+            eprintln!("ℹ️  '{}' bei {} Zeilen → keine Zeilen", segment, max_zeile);
+        } else {
+            eprintln!("ℹ️  '{}' bei {} Zeilen → Zeilen {}", segment, max_zeile, zeilen);
+        }
+    }
+    let zeilen = ZeilenParser::zeilen_set(&angabe, max_zeile)?;
+    let ausserhalb = zeilen.ausserhalb(1, i64::from(max_zeile));
+    if !ausserhalb.is_empty() {
+        eprintln!("⚠️  {} Zeilen außerhalb der Tabelle (1-{}) werden ignoriert: {}", ausserhalb.anzahl(), max_zeile, ausserhalb.kurz(10));
+    }
+
+    Ok((zeilen.begrenzt(1, i64::from(max_zeile)), angabe.rpnn_segmente().cloned().collect()))
+}
+
+// Löst erste und zweite Spalte (auch Aliase) zu den kanonischen Namen auf; ein mehrdeutiger
+// zweiter Name bleibt unverändert
+fn resolve_pair<'a>(csv_data: &'a CsvData, first: &str, second: &str) -> Result<(&'a str, &'a str)> {
+    let first = resolve_first(csv_data, first)?;
+    let aufloesung = csv_data
+        .resolve_second(first, second)
+        .ok_or_else(|| anyhow!("Unbekannte zweite Spalte für '{}': '{}'", first, second))?;
+    if let Aufloesung::Mehrdeutig { name, eintraege } = &aufloesung {
+        eprintln!("ℹ️  '{}' gehört zu mehreren Einträgen ({}); verwendet werden die Zeilen aller Einträge", name, eintraege.join(", "));
+    }
+    Ok((first, aufloesung.name()))
+}
+
+fn resolve_first<'a>(csv_data: &'a CsvData, first: &str) -> Result<&'a str> {
+    csv_data
+        .resolve_first(first)
+        .ok_or_else(|| anyhow!("Unbekannte erste Spalte: '{}'", first))
+}
+
+fn list_first(csv_data: &CsvData, format: Ausgabeformat) -> Result<()> {
+    if format == Ausgabeformat::Json {
+        return print_json(&EintragListe::new(None, &csv_data.all_first_columns));
+    }
+
+    for first in &csv_data.all_first_columns {
+        println!("{}", first.name);
+    }
+
+    Ok(())
+}
+
+fn list_second(csv_data: &CsvData, first: &str, format: Ausgabeformat) -> Result<()> {
+    let first = resolve_first(csv_data, first)?;
+    let seconds = csv_data
+        .get_seconds_for_first(first)
+        .ok_or_else(|| anyhow!("Unbekannte erste Spalte: '{}'", first))?;
+
+    if format == Ausgabeformat::Json {
+        return print_json(&EintragListe::new(Some(first), seconds));
+    }
+
+    for second in seconds {
+        println!("{}", second.name);
+    }
+
+    Ok(())
+}
+
+fn lookup(
+    csv_data: &CsvData,
+    first: &str,
+    second: &str,
+    zeilen: Option<&str>,
+    format: Ausgabeformat,
+) -> Result<()> {
+    let (first, second) = resolve_pair(csv_data, first, second)?;
+    let pair_rows = csv_data.get_row_numbers_for_pair(first, second);
+    if pair_rows.is_empty() {
+        bail!("Keine CSV-Zeilen für '{}' → '{}'", first, second);
+    }
+
+    let selected = zeilen
+        .map(|zeilen| parse_zeilen(zeilen, csv_data).map(|(rows, _)| rows))
+        .transpose()?;
+
+    if format == Ausgabeformat::Json {
+        let selected = selected.map(|selected| selected.zeilen(csv_data.raw_data.len() as i32));
+        return print_json(&PaarErgebnis::new(csv_data, first, second, selected.as_deref(), None));
+    }
+
+    let rows = match selected {
+        Some(selected) => pair_rows.into_iter().filter(|&row_num| selected.contains(i64::from(row_num))).collect(),
+        None => pair_rows,
+    };
+
+    for row_num in rows {
+        let (first_entry, second_entries, numbers) = &csv_data.raw_data[(row_num - 1) as usize];
+        println!("{}\t{}", row_num, format_csv_row(first_entry, second_entries, numbers));
+    }
+
+    Ok(())
+}
+
+fn command(
+    csv_data: &CsvData,
+    first: &str,
+    second: &str,
+    zeilen: &str,
+    nach_nummern: bool,
+    optionen: &KommandoOptionen,
+    format: Ausgabeformat,
+) -> Result<()> {
+    // Der Befehl verwendet die kanonischen Namen (mehrdeutige zweite Namen unverändert)
+    let (first, second) = resolve_pair(csv_data, first, second)?;
+
+    // Leere Zeilenangabe wählt wie im Assistenten alle Zeilen des Paares
+    let (rows, rpnn_segmente) = if zeilen.trim().is_empty() {
+        (csv_data.get_row_numbers_for_pair(first, second).into_iter().collect(), Vec::new())
+    } else {
+        parse_zeilen(zeilen, csv_data)?
+    };
+    if rows.is_empty() {
+        bail!("Zeilenangabe '{}' wählt keine existierenden Zeilen aus", zeilen.trim());
+    }
+
+    let spalten_nummern = csv_data.find_column_numbers_for_pair(first, second);
+    let auswahl = if nach_nummern {
+        if spalten_nummern.is_empty() {
+            bail!("Keine Spaltennummern für '{}' → '{}' vorhanden", first, second);
+        }
+        SpaltenAuswahl::Nummern {
+            flag: &optionen.spalten_flag,
+            nummern: &spalten_nummern,
+        }
+    } else {
+        SpaltenAuswahl::Name { first, second }
+    };
+
+    // `v`-Segmente und Brüche bleiben im Befehl erhalten, übrige Zeilen werden zu Bereichen
+    let zeilen_string = format_zeilenangabe_fuer_kommando(&rpnn_segmente, &rows, csv_data.raw_data.len() as i32)?;
+    let behalten = kommando::segmente_im_kommando(&rpnn_segmente, &zeilen_string);
+    if !behalten.is_empty() {
+        eprintln!(
+            "ℹ️  {} wertet rpnn gegen die Länge seiner eigenen Tabelle aus; die Zeilen oben gelten für die {} Zeilen dieser CSV-Tabelle",
+            behalten.join(", "),
+            csv_data.raw_data.len()
+        );
+    }
+    let befehl = kommando::build_command(optionen, &optionen.programm, &zeilen_string, &auswahl);
+
+    if format == Ausgabeformat::Json {
+        let rows = rows.zeilen(csv_data.raw_data.len() as i32);
+        return print_json(&PaarErgebnis::new(csv_data, first, second, Some(&rows), Some(befehl)));
+    }
+
+    println!("{}", befehl);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpnn::befehlsbibliothek::GespeicherterBefehl;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_take_option() {
+        let (wert, rest) = take_option(&args(&["lookup", "--zeilen", "1-5", "a", "b"]), "--zeilen").unwrap();
+        assert_eq!(wert.as_deref(), Some("1-5"));
+        assert_eq!(rest, args(&["lookup", "a", "b"]));
+
+        let (wert, rest) = take_option(&args(&["--zeilen=3", "a"]), "--zeilen").unwrap();
+        assert_eq!(wert.as_deref(), Some("3"));
+        assert_eq!(rest, args(&["a"]));
+
+        // Wiederholt: der letzte Wert gilt
+        let (wert, _) = take_option(&args(&["--zeilen", "1", "--zeilen=2"]), "--zeilen").unwrap();
+        assert_eq!(wert.as_deref(), Some("2"));
+
+        let (wert, rest) = take_option(&args(&["a"]), "--zeilen").unwrap();
+        assert_eq!((wert, rest), (None, args(&["a"])));
+
+        let fehler = take_option(&args(&["a", "--zeilen"]), "--zeilen").unwrap_err();
+        assert!(fehler.to_string().contains("erwartet einen Wert"));
+    }
+
+    #[test]
+    fn test_take_options_und_flag() {
+        let (werte, rest) = take_options(&args(&["--rpnn-arg", "-q", "x", "--rpnn-arg=--farbe"]), "--rpnn-arg").unwrap();
+        assert_eq!(werte, args(&["-q", "--farbe"]));
+        assert_eq!(rest, args(&["x"]));
+        assert!(take_options(&args(&["--rpnn-arg"]), "--rpnn-arg").is_err());
+
+        assert_eq!(take_flag(&args(&["a", "--nummern", "b"]), "--nummern"), (true, args(&["a", "b"])));
+        assert_eq!(take_flag(&args(&["a"]), "--nummern"), (false, args(&["a"])));
+    }
+
+    #[test]
+    fn test_expect_positionals() {
+        let eingabe = args(&["a", "b"]);
+        assert_eq!(expect_positionals(&eingabe, 2, "lookup").unwrap(), eingabe.as_slice());
+
+        let fehler = expect_positionals(&args(&["a", "b", "c"]), 2, "lookup").unwrap_err();
+        assert!(fehler.to_string().starts_with("Erwartet 2 Argument(e), erhalten 3"));
+
+        let fehler = expect_positionals(&args(&["a", "--zeilne", "1"]), 2, "lookup").unwrap_err();
+        assert!(fehler.to_string().starts_with("Unbekannte Option '--zeilne'"));
+    }
+
+    #[test]
+    fn test_konfiguration_nur_fuer_befehle() {
+        let path = std::env::temp_dir().join(format!("rpnn-config-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "kommando": { "zeilenflag": "--x" } }"#).unwrap();
+
+        let eingabe = args(&["--config", &path.display().to_string(), "--rpnn-arg", "-q", "help"]);
+        let (kommando_args, rest) = take_kommando_args(&eingabe).unwrap();
+        assert_eq!(rest, args(&["help"]));
+        assert_eq!(kommando_args.zusatz_args, args(&["-q"]));
+
+        // Die ungültige Datei stört `help` nicht, erst das Bauen eines Befehls
+        assert!(run(&rest, &CsvQuelle::Eingebettet, &kommando_args, Ausgabeformat::Text).is_ok());
+        assert!(kommando_args.optionen().is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_unbekanntes_unterkommando() {
+        let fehler = run(&args(&["lookpu"]), &CsvQuelle::Eingebettet, &KommandoArgs::default(), Ausgabeformat::Text)
+            .unwrap_err();
+        assert!(fehler.to_string().starts_with("Unbekanntes Unterkommando 'lookpu'"));
+        assert!(run(&[], &CsvQuelle::Eingebettet, &KommandoArgs::default(), Ausgabeformat::Text).is_err());
+    }
+
+    #[test]
+    fn test_bibliothek_unterkommando() {
+        let path = std::env::temp_dir().join(format!("rpnn-cli-befehle-{}.json", std::process::id()));
+        let mut bibliothek = Befehlsbibliothek::default();
+        bibliothek
+            .hinzufuegen(GespeicherterBefehl {
+                name: "hallo".to_string(),
+                befehl: "true".to_string(),
+                programm: "true".to_string(),
+                args: Vec::new(),
+                gespeichert: "2024-05-01 12:00:00".to_string(),
+            })
+            .unwrap();
+        bibliothek.speichern(&path).unwrap();
+
+        // Der Pfad wird übergeben statt RPNN_BEFEHLE zu setzen; die Umgebung teilen sich alle Tests
+        let bibliothek_run = |rest: &[&str]| super::bibliothek(&args(rest), &path, Ausgabeformat::Text);
+        assert!(bibliothek_run(&["list"]).is_ok());
+        assert!(bibliothek_run(&["run"]).is_err());
+        assert!(bibliothek_run(&["sortieren"]).unwrap_err().to_string().starts_with("Unbekannte Aktion 'sortieren'"));
+
+        bibliothek_run(&["delete", "hallo"]).unwrap();
+        assert!(Befehlsbibliothek::laden(&path).unwrap().befehle().is_empty());
+        assert!(bibliothek_run(&["delete", "hallo"]).unwrap_err().to_string().starts_with("Kein gespeicherter Befehl 'hallo'"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
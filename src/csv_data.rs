@@ -1,109 +1,334 @@
 use std::collections::HashMap;
-use crate::autocomplete::SimpleAutocomplete;
+use crate::autocomplete::{SimpleAutocomplete, strip_alias_hint};
+use crate::csv_index::CsvIndex;
+use crate::normalisierung::normalize;
+use crate::csv_parser::{CsvQuelle, CsvRow, CsvTabelle, Eintrag, Spaltennummern};
+use anyhow::Result;
+
+/// Ergebnis der Auflösung eines zweiten Namens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aufloesung<'a> {
+    /// Der Name gehört zu genau einem Eintrag; geliefert wird dessen kanonischer Name
+    Eindeutig(&'a str),
+    /// Der Name gehört zu mehreren Einträgen (z.B. `einklinken`); er bleibt unverändert und
+    /// wählt die Zeilen aller dieser Einträge
+    Mehrdeutig { name: &'a str, eintraege: Vec<&'a str> },
+}
+
+impl<'a> Aufloesung<'a> {
+    /// Der Name für Abfragen und den Befehl
+    pub fn name(&self) -> &'a str {
+        match self {
+            Aufloesung::Eindeutig(name) | Aufloesung::Mehrdeutig { name, .. } => name,
+        }
+    }
+}
 
 pub struct CsvData {
-    // Für jede erste Spalte (alle Varianten) speichern wir die zugehörigen zweiten Spalten
-    pub first_to_seconds_map: HashMap<String, Vec<String>>,
-    // Alle ersten Spalten für Autocomplete
-    pub all_first_columns: Vec<String>,
-    // Rohdaten für Detailsuche
-    pub raw_data: Vec<(Vec<String>, Vec<String>, String)>,
+    /// Für jede erste Spalte (kanonischer Name) speichern wir die zugehörigen zweiten Einträge
+    pub first_to_seconds_map: HashMap<String, Vec<Eintrag>>,
+    /// Alle ersten Einträge (kanonischer Name mit Aliasen) für Autocomplete
+    pub all_first_columns: Vec<Eintrag>,
+    /// Rohdaten für Detailsuche
+    pub raw_data: Vec<CsvRow>,
+    /// Warnungen zu fehlerhaften CSV-Zeilen (mit der Zeile in der Datei, `Dateizeile`)
+    pub warnungen: Vec<String>,
+    // Index Name → Zeilen, Paar → Zeilen und Name → Einträge, einmalig beim Laden aufgebaut
+    index: CsvIndex,
 }
 
 impl CsvData {
-    pub fn new() -> Self {
+    /// Lädt die Tabelle aus der Quelle; Warnungen landen in `warnungen`
+    pub fn load(quelle: &CsvQuelle) -> Result<Self> {
         use crate::csv_parser::CsvParser;
         
-        let (first_to_seconds_map, all_first_columns, raw_data) = CsvParser::load_all_data();
+        let (tabelle, warnungen) = CsvParser::load_all_data(quelle)?;
+        Ok(Self { warnungen, ..Self::from_tabelle(tabelle) })
+    }
+    
+    /// Übernimmt eine geparste Tabelle und baut den Index auf
+    pub fn from_tabelle((first_to_seconds_map, all_first_columns, raw_data): CsvTabelle) -> Self {
+        let index = CsvIndex::build(&raw_data, &all_first_columns, &first_to_seconds_map);
         
         Self {
             first_to_seconds_map,
             all_first_columns,
             raw_data,
+            warnungen: Vec::new(),
+            index,
         }
     }
     
-    // Hole Autocomplete für erste Spalte
+    /// Hole Autocomplete für erste Spalte
     pub fn get_first_level_autocomplete(&self) -> SimpleAutocomplete {
         SimpleAutocomplete::new(self.all_first_columns.clone())
     }
     
-    // Hole Autocomplete für zweite Spalte basierend auf erster Auswahl
+    /// Hole Autocomplete für zweite Spalte basierend auf erster Auswahl
     pub fn get_second_level_autocomplete(&self, first: &str) -> Option<SimpleAutocomplete> {
         self.first_to_seconds_map
             .get(first)
             .map(|seconds| SimpleAutocomplete::new(seconds.clone()))
     }
     
-    // Hole zweite Spalten für eine erste Spalte
-    pub fn get_seconds_for_first(&self, first: &str) -> Option<&Vec<String>> {
+    /// Hole zweite Spalten für eine erste Spalte
+    pub fn get_seconds_for_first(&self, first: &str) -> Option<&Vec<Eintrag>> {
         self.first_to_seconds_map.get(first)
     }
     
-    // Finde zugehörige Zahlen für ein Paar
-    pub fn find_numbers_for_pair(&self, first: &str, second: &str) -> Vec<String> {
-        let mut results = Vec::new();
+    /// Löst eine Eingabe (Name, Alias oder Vorschlag mit Alias-Hinweis) zum kanonischen ersten Namen auf;
+    /// zuerst exakt, dann in normalisierter Schreibweise (Groß-/Kleinschreibung, Umlaute, ß, Unterstriche egal)
+    pub fn resolve_first(&self, input: &str) -> Option<&str> {
+        let input = strip_alias_hint(input.trim());
+        self.index
+            .first_eintraege(input)
+            .first()
+            .map(|&position| self.all_first_columns[position].name.as_str())
+    }
+    
+    /// Löst eine Eingabe innerhalb einer ersten Spalte auf; gehört der Name zu mehreren
+    /// Einträgen, wird das als `Aufloesung::Mehrdeutig` gemeldet statt einen auszuwählen
+    pub fn resolve_second(&self, first: &str, input: &str) -> Option<Aufloesung<'_>> {
+        let seconds = self.get_seconds_for_first(first)?;
+        let input = strip_alias_hint(input.trim());
         
-        for (first_cols, second_cols, numbers) in &self.raw_data {
-            if first_cols.contains(&first.to_string()) && second_cols.contains(&second.to_string()) {
-                results.push(numbers.clone());
+        match *self.index.second_eintraege(first, input) {
+            [] => None,
+            [position] => Some(Aufloesung::Eindeutig(&seconds[position].name)),
+            ref positionen @ [erste, ..] => {
+                // Die Schreibweise aus der Tabelle, nicht die der Eingabe
+                let input_norm = normalize(input);
+                let name = seconds[erste]
+                    .alle_namen()
+                    .find(|n| *n == input)
+                    .or_else(|| seconds[erste].alle_namen().find(|n| normalize(n) == input_norm))?;
+                Some(Aufloesung::Mehrdeutig {
+                    name,
+                    eintraege: positionen.iter().map(|&p| seconds[p].name.as_str()).collect(),
+                })
             }
         }
-        
-        results
     }
     
-    // Zeige alle Details für ein erstes Wort
-    pub fn show_details_for_first(&self, first: &str) {
-        println!("\n🔍 Details für '{}':", first);
+    // Zeilen-Indizes eines Paares; beide Namen dürfen Aliase oder anders geschriebene Namen sein
+    fn rows_for_pair(&self, first: &str, second: &str) -> &[usize] {
+        match self.resolve_first(first) {
+            Some(first) => self.index.rows_for_pair(first, strip_alias_hint(second.trim())),
+            None => &[],
+        }
+    }
+    
+    /// Finde zugehörige Zahlen für ein Paar
+    pub fn find_numbers_for_pair(&self, first: &str, second: &str) -> Vec<&Spaltennummern> {
+        self.rows_for_pair(first, second)
+            .iter()
+            .map(|&index| &self.raw_data[index].2)
+            .collect()
+    }
+    
+    /// Alle Spaltennummern eines Paares (Reihenfolge des ersten Auftretens, ohne Duplikate)
+    pub fn find_column_numbers_for_pair(&self, first: &str, second: &str) -> Vec<u32> {
+        let mut zahlen = Vec::new();
         
-        if let Some(seconds) = self.get_seconds_for_first(first) {
-            println!("  📋 Verfügbare zweite Spalten ({}):", seconds.len());
-            for (i, second) in seconds.iter().enumerate().take(10) {
-                print!("    {:2}. {}", i + 1, second);
-                
-                // Zeige zugehörige Nummern
-                let numbers = self.find_numbers_for_pair(first, second);
-                if !numbers.is_empty() {
-                    print!(" → {}", numbers.join(", "));
+        for numbers in self.find_numbers_for_pair(first, second) {
+            for zahl in numbers.zahlen() {
+                if !zahlen.contains(&zahl) {
+                    zahlen.push(zahl);
                 }
-                println!();
-            }
-            
-            if seconds.len() > 10 {
-                println!("    ... und {} weitere", seconds.len() - 10);
             }
-        } else {
-            println!("  ⚠️  Keine zugehörigen zweiten Spalten gefunden");
         }
+        
+        zahlen
     }
     
-    // Zeige vollständige Informationen zu einem Paar
-    pub fn show_pair_details(&self, first: &str, second: &str) {
-        println!("\n🔍 Vollständige Informationen:");
-        println!("  Erste Spalte:  {}", first);
-        println!("  Zweite Spalte: {}", second);
+    /// Prüft ob eine (1-basierte) Zeile das Paar enthält
+    pub fn row_matches_pair(&self, row_num: i32, first: &str, second: &str) -> bool {
+        if row_num < 1 {
+            return false;
+        }
         
-        let numbers = self.find_numbers_for_pair(first, second);
-        if !numbers.is_empty() {
-            println!("  Zugehörige Nummern: {}", numbers.join(", "));
-        } else {
-            println!("  ℹ️  Keine zugehörigen Nummern gefunden");
+        self.rows_for_pair(first, second).binary_search(&((row_num - 1) as usize)).is_ok()
+    }
+    
+    /// Alle (1-basierten) Zeilennummern mit diesem ersten Namen (kanonisch, Alias oder anders geschrieben)
+    pub fn get_row_numbers_for_first(&self, first: &str) -> Vec<i32> {
+        self.index
+            .rows_for_first(strip_alias_hint(first.trim()))
+            .iter()
+            .map(|&index| (index + 1) as i32)
+            .collect()
+    }
+    
+    /// Alle (1-basierten) Zeilennummern, die das Paar enthalten; wie bei `resolve_first` und
+    /// `resolve_second` zählen Aliase und normalisierte Schreibweisen
+    pub fn get_row_numbers_for_pair(&self, first: &str, second: &str) -> Vec<i32> {
+        self.rows_for_pair(first, second)
+            .iter()
+            .map(|&index| (index + 1) as i32)
+            .collect()
+    }
+    
+    /// Teilt Zeilennummern in existierende und nicht existierende auf
+    pub fn split_existing_rows(&self, zeilen_numbers: &[i32]) -> (Vec<i32>, Vec<i32>) {
+        let max_row = self.raw_data.len() as i32;
+        zeilen_numbers
+            .iter()
+            .partition(|&&row_num| row_num >= 1 && row_num <= max_row)
+    }
+}
+
+/// Formatiert eine CSV-Zeile für die Ausgabe
+pub fn format_csv_row(first: &Eintrag, seconds: &[Eintrag], numbers: &Spaltennummern) -> String {
+    let second_fmt = if seconds.len() == 1 {
+        seconds[0].to_string()
+    } else {
+        let names: Vec<String> = seconds.iter().map(|e| e.to_string()).collect();
+        format!("{{{}}}", names.join(", "))
+    };
+    
+    format!("{} → {} → {}", first, second_fmt, numbers)
+}
+
+/// Formatiert Spaltennummern als kommagetrennte Liste
+pub fn format_zahlen(zahlen: &[u32]) -> String {
+    zahlen.iter().map(|z| z.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::csv_parser::{CsvParser, CSV_DATA};
+    
+    // Bisherige Suche: lineare Suche über alle Zeilen und alle Namen der zweiten Spalte
+    fn scan_rows_for_pair(raw_data: &[CsvRow], first: &str, second: &str) -> Vec<i32> {
+        let mut row_numbers = Vec::new();
+        for (i, (first_entry, second_entries, _)) in raw_data.iter().enumerate() {
+            if first_entry.name == first && second_entries.iter().any(|e| e.alle_namen().any(|n| n == second)) {
+                row_numbers.push((i + 1) as i32);
+            }
         }
+        row_numbers
+    }
+    
+    fn embedded_data() -> CsvData {
+        CsvData::from_tabelle(CsvParser::parse_csv_text(CSV_DATA).0)
+    }
+    
+    #[test]
+    fn test_index_matches_linear_scan() {
+        // Vergrößerte Tabelle: jede Abfrage muss genau die Zeilen der linearen Suche liefern
+        let einfach = embedded_data();
+        let text = CSV_DATA.repeat(3);
+        let csv_data = CsvData::from_tabelle(CsvParser::parse_csv_text(&text).0);
         
-        // Zeige alle CSV-Zeilen mit diesem Paar
-        println!("\n  📄 CSV-Zeilen mit diesem Paar:");
-        let mut found = false;
-        for (i, (first_cols, second_cols, nums)) in self.raw_data.iter().enumerate() {
-            if first_cols.contains(&first.to_string()) && second_cols.contains(&second.to_string()) {
-                println!("    Zeile {}: {:?} → {:?} → {}", 
-                    i + 1, first_cols, second_cols, nums);
-                found = true;
+        for (first, seconds) in &csv_data.first_to_seconds_map {
+            for name in seconds.iter().flat_map(Eintrag::alle_namen) {
+                let expected = scan_rows_for_pair(&csv_data.raw_data, first, name);
+                assert_eq!(csv_data.get_row_numbers_for_pair(first, name), expected);
+                assert_eq!(expected.len(), 3 * einfach.get_row_numbers_for_pair(first, name).len());
+                assert!(expected.iter().all(|&row| csv_data.row_matches_pair(row, first, name)));
             }
+            
+            let expected: Vec<i32> = (1..=csv_data.raw_data.len() as i32)
+                .filter(|&row| csv_data.raw_data[(row - 1) as usize].0.name == *first)
+                .collect();
+            assert_eq!(csv_data.get_row_numbers_for_first(first), expected);
+        }
+        
+        assert!(csv_data.get_row_numbers_for_pair("gibt es nicht", "auch nicht").is_empty());
+        assert!(!csv_data.row_matches_pair(0, "Religionen", "der_Tierkreiszeichen"));
+    }
+    
+    // Vergleich Index vs. lineare Suche auf einer vergrößerten Tabelle:
+    // cargo test --release -- --ignored bench_index_vs_scan --nocapture
+    #[test]
+    #[ignore]
+    fn bench_index_vs_scan() {
+        let text = CSV_DATA.repeat(200);
+        let csv_data = CsvData::from_tabelle(CsvParser::parse_csv_text(&text).0);
+        let pairs: Vec<(String, String)> = csv_data.first_to_seconds_map
+            .iter()
+            .flat_map(|(first, seconds)| seconds.iter().map(move |s| (first.clone(), s.name.clone())))
+            .collect();
+        
+        let start = Instant::now();
+        let scan_total: usize = pairs.iter()
+            .map(|(first, second)| scan_rows_for_pair(&csv_data.raw_data, first, second).len())
+            .sum();
+        let scan_time = start.elapsed();
+        
+        let start = Instant::now();
+        let index_total: usize = pairs.iter()
+            .map(|(first, second)| csv_data.get_row_numbers_for_pair(first, second).len())
+            .sum();
+        let index_time = start.elapsed();
+        
+        assert_eq!(scan_total, index_total);
+        println!("{} Zeilen, {} Paare", csv_data.raw_data.len(), pairs.len());
+        println!("Lineare Suche: {:?}", scan_time);
+        println!("Index:         {:?}", index_time);
+    }
+    
+    #[test]
+    fn test_aufloesung_ueber_index() {
+        let csv_data = embedded_data();
+        
+        // Kanonischer Name, Alias und normalisierte Schreibweise führen zum selben Eintrag
+        for input in ["Religionen", "religion", "RELIGIONEN", "Religionen  ← religion"] {
+            assert_eq!(csv_data.resolve_first(input), Some("Religionen"), "{}", input);
         }
+        assert_eq!(
+            csv_data.resolve_second("Religionen", "der tierkreiszeichen"),
+            Some(Aufloesung::Eindeutig("der_Tierkreiszeichen"))
+        );
+        assert_eq!(csv_data.resolve_first("gibt es nicht"), None);
+        assert_eq!(csv_data.resolve_second("Religionen", "gibt es nicht"), None);
+    }
+    
+    #[test]
+    fn test_namen_in_mehreren_eintraegen() {
+        let csv_data = embedded_data();
         
-        if !found {
-            println!("    ⚠️ Keine direkten Einträge gefunden");
+        // Dieselben Namen in anderer Reihenfolge sind ein Eintrag mit beiden Zeilen
+        assert_eq!(csv_data.resolve_second("Kontinuum", "F"), Some(Aufloesung::Eindeutig("Kontinuen")));
+        assert_eq!(csv_data.get_row_numbers_for_pair("Kontinuum", "Kontinuen"), vec![362, 363]);
+        assert_eq!(csv_data.get_row_numbers_for_pair("Kontinuum", "F"), vec![362, 363]);
+        
+        // `einklinken` ist Alias zweier verschiedener Einträge
+        let einklinken = csv_data.resolve_second("Eigenschaften_n", "Einklinken").unwrap();
+        assert_eq!(
+            einklinken,
+            Aufloesung::Mehrdeutig {
+                name: "einklinken",
+                eintraege: vec!["einklinken_vertrauen_anprangern", "Forschen_Erfinden_Einklinken"],
+            }
+        );
+        assert_eq!(csv_data.get_row_numbers_for_pair("Eigenschaften_n", einklinken.name()), vec![302, 308]);
+        
+        let kanonisch = csv_data.resolve_second("Eigenschaften_n", "einklinken_vertrauen_anprangern").unwrap();
+        assert_eq!(csv_data.get_row_numbers_for_pair("Eigenschaften_n", kanonisch.name()), vec![302]);
+    }
+    
+    #[test]
+    fn test_paar_abfragen_normalisiert() {
+        let csv_data = embedded_data();
+        
+        // Abfragen über die öffentliche API finden Paare auch in anderer Schreibweise
+        let erwartet = csv_data.get_row_numbers_for_pair("Planet(10_und_oder_12)", "Komplexität");
+        assert!(!erwartet.is_empty());
+        for (first, second) in [("planet", "KOMPLEXITAET"), ("PLANET(10 und oder 12)", "komplexitaet")] {
+            assert_eq!(csv_data.get_row_numbers_for_pair(first, second), erwartet, "{} / {}", first, second);
+            assert!(erwartet.iter().all(|&row| csv_data.row_matches_pair(row, first, second)));
+            assert_eq!(
+                csv_data.find_numbers_for_pair(first, second),
+                csv_data.find_numbers_for_pair("Planet(10_und_oder_12)", "Komplexität")
+            );
         }
+        
+        let superkraefte = csv_data.get_row_numbers_for_pair("Galaxie", "Superkräfte");
+        assert_eq!(csv_data.get_row_numbers_for_pair("galaxien", "SUPERKRAEFTE"), superkraefte);
+        assert_eq!(csv_data.get_row_numbers_for_first("GALAXIEN"), csv_data.get_row_numbers_for_first("Galaxie"));
+        assert!(csv_data.get_row_numbers_for_pair("Galaxie", "Superkraft").is_empty());
     }
 }
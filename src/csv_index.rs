@@ -0,0 +1,118 @@
+// csv_index.rs - Invertierter Index über die CSV-Rohdaten
+//
+// Wird einmalig beim Laden aufgebaut. Jeder Name und Alias ist zweimal verzeichnet: roh und in
+// normalisierter Schreibweise (`normalisierung::normalize`), damit weder Abfragen noch die
+// Namensauflösung über alle Zeilen oder Einträge laufen müssen.
+use std::collections::HashMap;
+use crate::csv_parser::{CsvRow, Eintrag};
+use crate::normalisierung::normalize;
+
+// Name → aufsteigende Indizes, roh und normalisiert
+#[derive(Debug, Default)]
+struct NamenIndex {
+    exakt: HashMap<String, Vec<usize>>,
+    normalisiert: HashMap<String, Vec<usize>>,
+}
+
+impl NamenIndex {
+    // Indizes müssen aufsteigend eingefügt werden
+    fn insert(&mut self, name: &str, index: usize) {
+        let listen = [
+            self.exakt.entry(name.to_string()).or_default(),
+            self.normalisiert.entry(normalize(name)).or_default(),
+        ];
+        for liste in listen {
+            // Mehrere Namen einer Zeile bzw. eines Eintrags können auf denselben Schlüssel fallen
+            if liste.last() != Some(&index) {
+                liste.push(index);
+            }
+        }
+    }
+    
+    fn exakt(&self, name: &str) -> &[usize] {
+        self.exakt.get(name).map_or(&[], Vec::as_slice)
+    }
+    
+    // Exakter Treffer, sonst Treffer in normalisierter Schreibweise
+    fn suchen(&self, input: &str) -> &[usize] {
+        match self.exakt(input) {
+            [] => self.normalisiert.get(&normalize(input)).map_or(&[], Vec::as_slice),
+            treffer => treffer,
+        }
+    }
+}
+
+// Zeilen-Indizes (0-basiert) je Name und je Paar, dazu die Einträge je Name für die Auflösung.
+// Jede Zeile steht unter allen Namen ihrer Einträge, auch wenn ein Name nur als Alias vorkommt
+// (`F, f, Sechs, Kontinuen`).
+#[derive(Debug, Default)]
+pub struct CsvIndex {
+    // Jeder Name der ersten Spalte → Zeilen
+    first_rows: NamenIndex,
+    // Kanonischer erster Name → jeder Name der zweiten Spalte → Zeilen
+    pair_rows: HashMap<String, NamenIndex>,
+    // Jeder Name der ersten Spalte → Positionen in `all_first_columns`
+    first_eintraege: NamenIndex,
+    // Kanonischer erster Name → jeder Name der zweiten Spalte → Positionen in dessen Einträgen
+    second_eintraege: HashMap<String, NamenIndex>,
+}
+
+impl CsvIndex {
+    // Baut den Index einmalig beim Laden auf
+    pub fn build(
+        raw_data: &[CsvRow],
+        all_first_columns: &[Eintrag],
+        first_to_seconds_map: &HashMap<String, Vec<Eintrag>>,
+    ) -> Self {
+        let mut index = Self::default();
+        
+        for (row, (first, seconds, _)) in raw_data.iter().enumerate() {
+            for name in first.alle_namen() {
+                index.first_rows.insert(name, row);
+            }
+            
+            let by_second = index.pair_rows.entry(first.name.clone()).or_default();
+            for name in seconds.iter().flat_map(Eintrag::alle_namen) {
+                by_second.insert(name, row);
+            }
+        }
+        
+        for (position, first) in all_first_columns.iter().enumerate() {
+            for name in first.alle_namen() {
+                index.first_eintraege.insert(name, position);
+            }
+        }
+        
+        for (first, seconds) in first_to_seconds_map {
+            let by_name = index.second_eintraege.entry(first.clone()).or_default();
+            for (position, second) in seconds.iter().enumerate() {
+                for name in second.alle_namen() {
+                    by_name.insert(name, position);
+                }
+            }
+        }
+        
+        index
+    }
+    
+    // Alle Zeilen-Indizes mit diesem ersten Namen (kanonisch oder Alias, exakt, sonst normalisiert)
+    pub fn rows_for_first(&self, first: &str) -> &[usize] {
+        self.first_rows.suchen(first)
+    }
+    
+    // Alle Zeilen-Indizes eines Paares; `first` ist kanonisch, `second` ein beliebiger Name
+    // oder Alias (exakt, sonst normalisiert)
+    pub fn rows_for_pair(&self, first: &str, second: &str) -> &[usize] {
+        self.pair_rows.get(first).map_or(&[], |by_second| by_second.suchen(second))
+    }
+    
+    // Positionen der ersten Einträge, zu denen die Eingabe passt (exakt, sonst normalisiert)
+    pub fn first_eintraege(&self, input: &str) -> &[usize] {
+        self.first_eintraege.suchen(input)
+    }
+    
+    // Positionen der zweiten Einträge einer ersten Spalte, zu denen die Eingabe passt
+    pub fn second_eintraege(&self, first: &str, input: &str) -> &[usize] {
+        self.second_eintraege.get(first).map_or(&[], |by_name| by_name.suchen(input))
+    }
+}
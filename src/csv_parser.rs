@@ -1,12 +1,154 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::Serialize;
 
-// CSV-Daten zur Kompilierzeit einbetten
-const CSV_DATA: &str = include_str!("../csv/coordinatesColumnsFirstReliTable.csv");
+/// CSV-Daten zur Kompilierzeit einbetten (Rückfall, wenn keine Datei angegeben ist)
+pub const CSV_DATA: &str = include_str!("../csv/coordinatesColumnsFirstReliTable.csv");
+
+/// Umgebungsvariable für den Pfad zur CSV-Datei
+pub const CSV_ENV_VAR: &str = "RPNN_CSV";
+
+/// Eine CSV-Zeile: (erste Spalte, zweite Spalten, Spaltennummern)
+pub type CsvRow = (Eintrag, Vec<Eintrag>, Spaltennummern);
+
+/// Geladene Tabelle: (erste → zweite Einträge, alle ersten Einträge, Rohdaten)
+pub type CsvTabelle = (HashMap<String, Vec<Eintrag>>, Vec<Eintrag>, Vec<CsvRow>);
+
+/// Ein Spalteneintrag: kanonischer Anzeigename plus alternative Schreibweisen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Eintrag {
+    pub name: String,
+    pub aliase: Vec<String>,
+}
+
+impl Eintrag {
+    /// Erster Name ist der kanonische, alle weiteren sind Aliase
+    pub fn from_names(names: Vec<String>) -> Option<Self> {
+        let mut names = names.into_iter();
+        let name = names.next()?;
+        Some(Self {
+            name,
+            aliase: names.collect(),
+        })
+    }
+    
+    /// Kanonischer Name gefolgt von allen Aliasen
+    pub fn alle_namen(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.name).chain(self.aliase.iter())
+    }
+    
+    // Gleiche Namen, möglicherweise in anderer Reihenfolge (anderer kanonischer Name)
+    fn gleiche_namen(&self, other: &Eintrag) -> bool {
+        self.alle_namen().all(|n| other.alle_namen().any(|o| o == n))
+            && other.alle_namen().all(|o| self.alle_namen().any(|n| n == o))
+    }
+    
+    // Übernimmt neue Aliase eines weiteren Vorkommens desselben Eintrags
+    fn merge_aliase(&mut self, other: &Eintrag) {
+        for alias in &other.aliase {
+            if *alias != self.name && !self.aliase.contains(alias) {
+                self.aliase.push(alias.clone());
+            }
+        }
+    }
+}
+
+impl fmt::Display for Eintrag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.aliase.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "({}, {})", self.name, self.aliase.join(", "))
+        }
+    }
+}
+
+/// Dritte CSV-Spalte: Spaltennummern der rpnn-Tabelle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "art", content = "werte", rename_all = "lowercase")]
+pub enum Spaltennummern {
+    // Geordnete Liste wie `[10, 5, 4, 8]`
+    Liste(Vec<u32>),
+    // Ungeordnete Menge im Python-Stil wie `{'2', '3'}`
+    Menge(BTreeSet<u32>),
+}
+
+impl Spaltennummern {
+    /// Alle Nummern (Liste in Originalreihenfolge, Menge aufsteigend)
+    pub fn zahlen(&self) -> Vec<u32> {
+        match self {
+            Spaltennummern::Liste(zahlen) => zahlen.clone(),
+            Spaltennummern::Menge(zahlen) => zahlen.iter().copied().collect(),
+        }
+    }
+}
+
+impl Default for Spaltennummern {
+    fn default() -> Self {
+        Spaltennummern::Liste(Vec::new())
+    }
+}
+
+impl fmt::Display for Spaltennummern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (open, close) = match self {
+            Spaltennummern::Liste(_) => ('[', ']'),
+            Spaltennummern::Menge(_) => ('{', '}'),
+        };
+        let zahlen: Vec<String> = self.zahlen().iter().map(|z| z.to_string()).collect();
+        write!(f, "{}{}{}", open, zahlen.join(", "), close)
+    }
+}
+
+/// Herkunft der CSV-Daten
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvQuelle {
+    // Zur Kompilierzeit eingebettete Kopie
+    Eingebettet,
+    // Zur Laufzeit gelesene Datei
+    Datei(PathBuf),
+}
+
+impl CsvQuelle {
+    /// Bestimmt die Quelle: `--csv <pfad>` vor `RPNN_CSV`, sonst eingebettet
+    pub fn resolve(cli_path: Option<String>) -> Self {
+        Self::aus_pfaden(cli_path, std::env::var(CSV_ENV_VAR).ok())
+    }
+    
+    /// Wie `resolve`, mit dem Wert von `RPNN_CSV` als Parameter statt aus der Umgebung
+    pub fn aus_pfaden(cli_path: Option<String>, env_path: Option<String>) -> Self {
+        cli_path
+            .or(env_path)
+            .filter(|path| !path.trim().is_empty())
+            .map(|path| CsvQuelle::Datei(PathBuf::from(path)))
+            .unwrap_or(CsvQuelle::Eingebettet)
+    }
+    
+    /// Liest den CSV-Text aus der Quelle
+    pub fn read_text(&self) -> Result<String> {
+        match self {
+            CsvQuelle::Eingebettet => Ok(CSV_DATA.to_string()),
+            CsvQuelle::Datei(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("CSV-Datei '{}' konnte nicht gelesen werden", path.display())),
+        }
+    }
+}
+
+impl fmt::Display for CsvQuelle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvQuelle::Eingebettet => write!(f, "eingebettete Tabelle"),
+            CsvQuelle::Datei(path) => write!(f, "Datei '{}'", path.display()),
+        }
+    }
+}
 
 pub struct CsvParser;
 
 impl CsvParser {
-    // Extrahiert ALLE Wörter aus der ersten Spalte
+    /// Extrahiert ALLE Wörter aus der ersten Spalte
     pub fn parse_first_column_all(text: &str) -> Vec<String> {
         let trimmed = text.trim();
         let mut result = Vec::new();
@@ -56,7 +198,7 @@ impl CsvParser {
         result
     }
     
-    // Extrahiert ALLE Wörter aus der zweiten Spalte
+    /// Extrahiert ALLE Wörter aus der zweiten Spalte
     pub fn parse_second_column(text: &str) -> Vec<String> {
         let mut result = Vec::new();
         let text = text.trim();
@@ -64,9 +206,8 @@ impl CsvParser {
         // Manuelles Parsing um Klammern und Kommas zu handhaben
         let mut current = String::new();
         let mut in_parentheses = 0;
-        let mut chars = text.chars().peekable();
         
-        while let Some(c) = chars.next() {
+        for c in text.chars() {
             match c {
                 '(' => {
                     in_parentheses += 1;
@@ -78,17 +219,13 @@ impl CsvParser {
                     }
                     current.push(c);
                 }
-                ',' => {
-                    if in_parentheses == 0 {
-                        // Ende eines Eintrags
-                        let trimmed = current.trim().to_string();
-                        if !trimmed.is_empty() {
-                            result.push(trimmed);
-                        }
-                        current.clear();
-                    } else {
-                        current.push(c);
+                ',' if in_parentheses == 0 => {
+                    // Ende eines Eintrags
+                    let trimmed = current.trim().to_string();
+                    if !trimmed.is_empty() {
+                        result.push(trimmed);
                     }
+                    current.clear();
                 }
                 _ => {
                     current.push(c);
@@ -105,15 +242,41 @@ impl CsvParser {
         result
     }
     
-    // Lädt alle CSV-Daten
-    pub fn load_all_data() -> (HashMap<String, Vec<String>>, Vec<String>, Vec<(Vec<String>, Vec<String>, String)>) {
-        let mut first_to_seconds_map: HashMap<String, HashSet<String>> = HashMap::new();
-        let mut all_first_set = HashSet::new();
-        let mut raw_data = Vec::new();
+    /// Zweite Spalte als Einträge: normalerweise ein Name mit Aliasen,
+    /// Python-Mengen wie `{'2', '3'}` dagegen sind mehrere eigenständige Einträge
+    pub fn parse_second_column_entries(text: &str) -> Vec<Eintrag> {
+        let trimmed = text.trim();
         
-        println!("📂 Lade CSV-Daten...");
+        if trimmed == "set()" {
+            return Vec::new();
+        }
+        
+        if let Some(inner) = trimmed.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+            return inner
+                .split(',')
+                .map(|s| s.trim().trim_matches('\''))
+                .filter(|s| !s.is_empty())
+                .map(|s| Eintrag { name: s.to_string(), aliase: Vec::new() })
+                .collect();
+        }
+        
+        Eintrag::from_names(Self::parse_second_column(trimmed)).into_iter().collect()
+    }
+    
+    /// Lädt alle CSV-Daten aus der angegebenen Quelle; liefert zusätzlich Warnungen zu fehlerhaften Zeilen
+    pub fn load_all_data(quelle: &CsvQuelle) -> Result<(CsvTabelle, Vec<String>)> {
+        let text = quelle.read_text()?;
+        Ok(Self::parse_csv_text(&text))
+    }
+    
+    /// Parst den kompletten CSV-Text; liefert zusätzlich Warnungen zu fehlerhaften Zeilen
+    pub fn parse_csv_text(text: &str) -> (CsvTabelle, Vec<String>) {
+        let mut first_entries: HashMap<String, Eintrag> = HashMap::new();
+        let mut first_to_seconds_map: HashMap<String, HashMap<String, Eintrag>> = HashMap::new();
+        let mut raw_data = Vec::new();
+        let mut warnungen = Vec::new();
         
-        for line in CSV_DATA.lines() {
+        for (line_index, line) in text.lines().enumerate() {
             let line = line.trim();
             if line.is_empty() {
                 continue;
@@ -125,76 +288,167 @@ impl CsvParser {
                 continue;
             }
             
-            // Erste Spalte: ALLE Wörter extrahieren
-            let first_columns = Self::parse_first_column_all(parts[0]);
-            if first_columns.is_empty() {
-                continue;
-            }
+            // Erste Spalte: kanonischer Name mit allen Aliasen
+            let first_entry = match Eintrag::from_names(Self::parse_first_column_all(parts[0])) {
+                Some(entry) => entry,
+                None => continue,
+            };
             
-            // Zweite Spalte: ALLE Wörter extrahieren
-            let second_columns = if let Some(second_part) = parts.get(1) {
-                Self::parse_second_column(second_part)
+            // Zweite Spalte: alle Einträge extrahieren
+            let second_entries = if let Some(second_part) = parts.get(1) {
+                Self::parse_second_column_entries(second_part)
             } else {
                 Vec::new()
             };
             
-            // Dritte Spalte (Zahlen)
-            let numbers = if let Some(third_part) = parts.get(2) {
-                third_part.to_string()
-            } else {
-                String::new()
+            // Dritte Spalte (Spaltennummern); fehlerhafte Einträge werden als leer übernommen
+            let numbers = match parts.get(2) {
+                Some(third_part) => Self::parse_third_column(third_part).unwrap_or_else(|err| {
+                    warnungen.push(format!(
+                        "Dateizeile {}: ungültige Zahlenspalte '{}': {}",
+                        line_index + 1, third_part.trim(), err
+                    ));
+                    Spaltennummern::default()
+                }),
+                None => Spaltennummern::default(),
             };
             
-            // Für jedes Wort in der ersten Spalte die zugehörigen zweiten Wörter speichern
-            for first in &first_columns {
-                all_first_set.insert(first.clone());
-                
-                // Füge alle zweiten Wörter für dieses erste Wort hinzu
-                let entry = first_to_seconds_map
-                    .entry(first.clone())
-                    .or_insert_with(HashSet::new);
-                
-                for second in &second_columns {
-                    entry.insert(second.clone());
-                }
+            // Einträge mit gleichem kanonischen Namen zusammenführen (Aliase vereinigen)
+            first_entries
+                .entry(first_entry.name.clone())
+                .and_modify(|existing| existing.merge_aliase(&first_entry))
+                .or_insert_with(|| first_entry.clone());
+            
+            // Zweite Einträge unter dem kanonischen ersten Namen speichern
+            let seconds = first_to_seconds_map
+                .entry(first_entry.name.clone())
+                .or_default();
+            
+            for second in &second_entries {
+                // `F, f, Sechs, Kontinuen` nach `Kontinuen, F, f, Sechs` ist derselbe Eintrag und
+                // wird unter dem zuerst gesehenen kanonischen Namen geführt
+                let key = seconds
+                    .values()
+                    .find(|existing| existing.name != second.name && existing.gleiche_namen(second))
+                    .map_or_else(|| second.name.clone(), |existing| existing.name.clone());
+                seconds
+                    .entry(key)
+                    .and_modify(|existing| existing.merge_aliase(second))
+                    .or_insert_with(|| second.clone());
             }
             
-            raw_data.push((first_columns, second_columns, numbers));
+            raw_data.push((first_entry, second_entries, numbers));
         }
         
-        // Konvertiere HashSets zu sortierten Vectors
-        let mut sorted_first_to_seconds: HashMap<String, Vec<String>> = HashMap::new();
-        for (first, seconds_set) in first_to_seconds_map {
-            let mut seconds: Vec<String> = seconds_set.into_iter().collect();
-            seconds.sort_by_key(|s| s.to_lowercase());
+        // Konvertiere HashMaps zu sortierten Vectors
+        let mut sorted_first_to_seconds: HashMap<String, Vec<Eintrag>> = HashMap::new();
+        for (first, seconds_map) in first_to_seconds_map {
+            let mut seconds: Vec<Eintrag> = seconds_map.into_values().collect();
+            seconds.sort_by_key(|s| s.name.to_lowercase());
             sorted_first_to_seconds.insert(first, seconds);
         }
         
         // Sortiere alle ersten Spalten
-        let mut all_first_columns: Vec<String> = all_first_set.into_iter().collect();
-        all_first_columns.sort_by_key(|s| s.to_lowercase());
-        
-        println!("✅ Geladen: {} verschiedene erste Spalten", all_first_columns.len());
-        println!("✅ Geladen: {} verschiedene Zuordnungen", sorted_first_to_seconds.len());
-        println!("✅ Geladen: {} CSV-Zeilen", raw_data.len());
+        let mut all_first_columns: Vec<Eintrag> = first_entries.into_values().collect();
+        all_first_columns.sort_by_key(|s| s.name.to_lowercase());
         
-        // Zeige Beispiele
-// csv_parser.rs - Entfernen der unbenutzten Variable
-// Zeilen 183-195:
-println!("\n🔍 Beispiel-Zuordnungen:");
-for (first, seconds) in sorted_first_to_seconds.iter().take(3) {
-    println!("  '{}' → {} Optionen", first, seconds.len());
-    for (i, second) in seconds.iter().take(3).enumerate() {
-        println!("     {}. {}", i + 1, second);
+        ((sorted_first_to_seconds, all_first_columns, raw_data), warnungen)
     }
-    if seconds.len() > 3 {
-        println!("     ... und {} weitere", seconds.len() - 3);
+    
+    /// Parst die dritte Spalte: `[10, 5, 4, 8]`, `[]` oder `{'2', '3'}`
+    pub fn parse_third_column(text: &str) -> Result<Spaltennummern, String> {
+        let trimmed = text.trim();
+        
+        let (inner, ist_menge) = if let Some(inner) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            (inner, false)
+        } else if let Some(inner) = trimmed.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+            (inner, true)
+        } else {
+            return Err("erwartet [..] oder {..}".to_string());
+        };
+        
+        let mut zahlen = Vec::new();
+        if !inner.trim().is_empty() {
+            for eintrag in inner.split(',') {
+                // Mengen aus Python enthalten die Zahlen als Strings: '2'
+                let eintrag = eintrag.trim().trim_matches(|c| c == '\'' || c == '"');
+                let zahl = eintrag
+                    .parse::<u32>()
+                    .map_err(|_| format!("'{}' ist keine Spaltennummer", eintrag))?;
+                zahlen.push(zahl);
+            }
+        }
+        
+        if ist_menge {
+            Ok(Spaltennummern::Menge(zahlen.into_iter().collect()))
+        } else {
+            Ok(Spaltennummern::Liste(zahlen))
+        }
     }
 }
 
-if sorted_first_to_seconds.len() > 3 {
-    println!("  ... und {} weitere Zuordnungen", sorted_first_to_seconds.len() - 3);
-}       
-        (sorted_first_to_seconds, all_first_columns, raw_data)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_third_column() {
+        assert_eq!(CsvParser::parse_third_column("[10, 5, 4, 8]"), Ok(Spaltennummern::Liste(vec![10, 5, 4, 8])));
+        assert_eq!(CsvParser::parse_third_column("[]"), Ok(Spaltennummern::Liste(Vec::new())));
+        assert_eq!(
+            CsvParser::parse_third_column("{'3', '2'}"),
+            Ok(Spaltennummern::Menge([2, 3].into_iter().collect()))
+        );
+        assert!(CsvParser::parse_third_column("[1, x]").is_err());
+        assert!(CsvParser::parse_third_column("10, 5").is_err());
+    }
+    
+    #[test]
+    fn test_aliases_are_merged_per_canonical_name() {
+        let text = "('Wichtigste', 'wichtigste');Wichtigste, wichtigste;[1]\n\
+                    ('Wichtigste', 'wichtigstes');Zweite, zweite;[2]\n\
+                    x;{'2', '3'};[]\n";
+        let ((first_to_seconds, all_first, _), _) = CsvParser::parse_csv_text(text);
+        
+        assert_eq!(all_first.len(), 2);
+        let wichtigste = all_first.iter().find(|e| e.name == "Wichtigste").unwrap();
+        assert_eq!(wichtigste.aliase, vec!["wichtigste", "wichtigstes"]);
+        assert_eq!(first_to_seconds["Wichtigste"].len(), 2);
+        
+        let set_names: Vec<&str> = first_to_seconds["x"].iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(set_names, vec!["2", "3"]);
+    }
+    
+    #[test]
+    fn test_parse_csv_text_warns_with_line_number() {
+        let ((_, _, raw_data), warnungen) = CsvParser::parse_csv_text("a;b;[1, 2]\n\nc;d;[oops]\n");
+        assert_eq!(raw_data.len(), 2);
+        assert_eq!(raw_data[1].2, Spaltennummern::default());
+        assert_eq!(raw_data[0].0.name, "a");
+        assert_eq!(warnungen.len(), 1);
+        assert!(warnungen[0].starts_with("Dateizeile 3:"));
+    }
+    
+    #[test]
+    fn test_quelle_vorrang_und_lesefehler() {
+        let path = std::env::temp_dir().join(format!("rpnn-quelle-{}.csv", std::process::id()));
+        std::fs::write(&path, "a;b;[1]\n").unwrap();
+        let datei = path.display().to_string();
+        
+        // Der Wert von RPNN_CSV wird übergeben; die Umgebung teilen sich alle Tests
+        let env = Some(datei.clone());
+        assert_eq!(CsvQuelle::aus_pfaden(None, env.clone()), CsvQuelle::Datei(path.clone()));
+        assert_eq!(
+            CsvQuelle::aus_pfaden(Some("andere.csv".into()), env),
+            CsvQuelle::Datei(PathBuf::from("andere.csv"))
+        );
+        assert_eq!(CsvQuelle::Datei(path.clone()).read_text().unwrap(), "a;b;[1]\n");
+        
+        assert_eq!(CsvQuelle::aus_pfaden(None, Some(" ".into())), CsvQuelle::Eingebettet);
+        assert_eq!(CsvQuelle::aus_pfaden(None, None), CsvQuelle::Eingebettet);
+        
+        std::fs::remove_file(&path).unwrap();
+        let fehler = CsvQuelle::Datei(path.clone()).read_text().unwrap_err();
+        assert_eq!(fehler.to_string(), format!("CSV-Datei '{}' konnte nicht gelesen werden", datei));
     }
 }
@@ -1,7 +1,7 @@
 // if_is_zeilen_angabe/mod.rs
 pub mod functions;
-pub mod split;
-pub mod validation;
+#[cfg(test)]
+mod validation;
 
 // Re-export der wichtigsten Funktionen
 pub use functions::*;
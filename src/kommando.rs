@@ -0,0 +1,293 @@
+// kommando.rs - Erzeugung des rpnn-Kommando-Aufrufs
+use std::process::{Command, ExitStatus};
+use anyhow::{Context, Result};
+use crate::zeilen_parser::{Segment, ZeilenParser, Zeilenangabe};
+use crate::zeilen_set::ZeilenSet;
+
+/// Pfad zum rpnn-Programm, wie er im generierten Befehl erscheint
+pub const RPNN_PFAD: &str = "../target/debug/mein-rpnn";
+/// Programmname für die vereinfachte (kopierbare) Version
+pub const RPNN_NAME: &str = "mein-rpnn";
+/// Standard-Flag für die Zeilenangabe
+pub const ZEILEN_FLAG: &str = "--vorhervonausschnitt";
+/// Standard-Flag für die Auswahl über Spaltennamen
+pub const SPALTENNAME_FLAG: &str = "--spaltenname";
+/// Standard-Flag für die Auswahl über Spaltennummern
+pub const SPALTEN_FLAG: &str = "--spalten";
+
+/// Einstellungen für die Befehlserzeugung (siehe auch `konfiguration`)
+#[derive(Debug, Clone)]
+pub struct KommandoOptionen {
+    /// Pfad zum rpnn-Programm im vollständigen Befehl und beim Ausführen
+    pub programm: String,
+    /// Programmname für die vereinfachte Version und die Suche im PATH
+    pub programm_name: String,
+    /// Flag vor der Zeilenangabe
+    pub zeilen_flag: String,
+    /// Flag vor den beiden Spaltennamen
+    pub spaltenname_flag: String,
+    /// Flag, mit dem rpnn konkrete Spaltennummern erhält
+    pub spalten_flag: String,
+    /// Argumente, die jedem Aufruf direkt nach dem Programm mitgegeben werden
+    pub zusatz_args: Vec<String>,
+}
+
+impl Default for KommandoOptionen {
+    fn default() -> Self {
+        Self {
+            programm: RPNN_PFAD.to_string(),
+            programm_name: RPNN_NAME.to_string(),
+            zeilen_flag: ZEILEN_FLAG.to_string(),
+            spaltenname_flag: SPALTENNAME_FLAG.to_string(),
+            spalten_flag: SPALTEN_FLAG.to_string(),
+            zusatz_args: Vec::new(),
+        }
+    }
+}
+
+/// Wie der generierte Befehl die Tabellenspalten auswählt
+pub enum SpaltenAuswahl<'a> {
+    // `--spaltenname <erste> <zweite>`
+    Name { first: &'a str, second: &'a str },
+    // `<flag> 10,5,4,8`
+    Nummern { flag: &'a str, nummern: &'a [u32] },
+}
+
+/// Baut den Kommando-String für eine Spaltenauswahl und eine formatierte Zeilenangabe;
+/// jedes Argument ist für eine POSIX-Shell gequotet
+pub fn build_command(optionen: &KommandoOptionen, programm: &str, zeilen_string: &str, auswahl: &SpaltenAuswahl) -> String {
+    let mut teile = vec![programm.to_string()];
+    teile.extend(build_args(optionen, zeilen_string, auswahl));
+    shell_join(&teile)
+}
+
+/// Quotet ein Argument für eine POSIX-Shell: harmlose Argumente bleiben unverändert,
+/// alle anderen kommen in einfache Anführungszeichen (`'` wird zu `'\''`)
+pub fn shell_quote(arg: &str) -> String {
+    // `=` wird ebenfalls gequotet, damit ein erstes Wort nie als Variablenzuweisung gilt
+    let harmlos = |c: char| c.is_ascii_alphanumeric() || "_@%+:,./-".contains(c);
+    if !arg.is_empty() && arg.chars().all(harmlos) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Verbindet Argumente zu einer Shell-Befehlszeile, die wieder genau diese Argumente ergibt
+pub fn shell_join<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter().map(|arg| shell_quote(arg.as_ref())).collect::<Vec<_>>().join(" ")
+}
+
+/// Die Argumente des rpnn-Aufrufs (ohne Programm), je Argument ein Eintrag; so können Namen
+/// mit Leerzeichen oder Sonderzeichen ohne Shell übergeben werden
+pub fn build_args(optionen: &KommandoOptionen, zeilen_string: &str, auswahl: &SpaltenAuswahl) -> Vec<String> {
+    let mut args = optionen.zusatz_args.clone();
+    args.extend([optionen.zeilen_flag.clone(), zeilen_string.to_string()]);
+    match auswahl {
+        SpaltenAuswahl::Name { first, second } => {
+            args.extend([optionen.spaltenname_flag.clone(), first.to_string(), second.to_string()]);
+        }
+        SpaltenAuswahl::Nummern { flag, nummern } => {
+            args.extend([flag.to_string(), format_spalten_fuer_kommando(nummern)]);
+        }
+    }
+    args
+}
+
+/// Ergebnis eines rpnn-Aufrufs mit der aufgefangenen Ausgabe
+#[derive(Debug)]
+pub struct Ausfuehrung {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Ausfuehrung {
+    /// Kurzfassung wie `Exit-Code 0 (erfolgreich), 12 Zeilen Ausgabe, 0 Zeilen Fehlerausgabe`
+    pub fn zusammenfassung(&self) -> String {
+        let status = match self.status.code() {
+            Some(0) => "Exit-Code 0 (erfolgreich)".to_string(),
+            Some(code) => format!("Exit-Code {} (Fehler)", code),
+            None => format!("abgebrochen ({})", self.status),
+        };
+        format!(
+            "{}, {} Zeilen Ausgabe, {} Zeilen Fehlerausgabe",
+            status,
+            self.stdout.lines().count(),
+            self.stderr.lines().count()
+        )
+    }
+    
+    /// Protokoll zum Speichern: Befehl, Zeitpunkt und Status als Kommentar, dann beide Ausgaben
+    pub fn protokoll(&self, befehl: &str, zeitpunkt: &str) -> String {
+        format!(
+            "# Befehl: {}\n# Ausgeführt: {}\n# {}\n\n## stdout\n{}\n## stderr\n{}",
+            befehl,
+            zeitpunkt,
+            self.zusammenfassung(),
+            self.stdout,
+            self.stderr
+        )
+    }
+}
+
+/// Startet das Programm direkt (ohne Shell) mit den Argumenten, wartet auf das Ende und fängt
+/// stdout und stderr auf (ungültiges UTF-8 wird ersetzt)
+pub fn ausfuehren(programm: &str, args: &[String]) -> Result<Ausfuehrung> {
+    let ausgabe = Command::new(programm)
+        .args(args)
+        .output()
+        .with_context(|| format!("'{}' konnte nicht gestartet werden", programm))?;
+    
+    Ok(Ausfuehrung {
+        status: ausgabe.status,
+        stdout: String::from_utf8_lossy(&ausgabe.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&ausgabe.stderr).into_owned(),
+    })
+}
+
+/// Formatiert Spaltennummern für den Kommando-Aufruf (Reihenfolge bleibt erhalten)
+pub fn format_spalten_fuer_kommando(nummern: &[u32]) -> String {
+    nummern.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Zeilenangabe für den Kommando-Aufruf: `v`-Segmente und Brüche (`Zeilenangabe::rpnn_segmente`)
+/// bleiben erhalten, da rpnn sie selbst auswertet; alle übrigen Zeilen werden zu Bereichen
+/// zusammengefasst. Mengenoperationen (`!`, `&`, `\`) kennt rpnn nicht, sie erscheinen daher immer
+/// aufgelöst; auch ein Segment, aus dem Zeilen ausgeschlossen wurden, wird als Bereiche ausgegeben
+pub fn format_zeilenangabe_fuer_kommando(rpnn_segmente: &[Segment], zeilen: &ZeilenSet, max_zeile: i32) -> Result<String> {
+    let mut teile = Vec::new();
+    let mut abgedeckt = ZeilenSet::new();
+    
+    for segment in rpnn_segmente {
+        let segment_zeilen = ZeilenParser::zeilen_set(&Zeilenangabe { segmente: vec![segment.clone()] }, max_zeile)?;
+        if segment_zeilen.begrenzt(1, i64::from(max_zeile)).differenz(zeilen).is_empty() {
+            abgedeckt = abgedeckt.vereinigung(&segment_zeilen);
+            teile.push(segment.to_string());
+        }
+    }
+    
+    let uebrige = zeilen.differenz(&abgedeckt);
+    if !uebrige.is_empty() {
+        teile.push(uebrige.to_string());
+    }
+    
+    Ok(teile.join(","))
+}
+
+/// Die `v`-Segmente und Brüche, die im Befehl erhalten geblieben sind. rpnn wertet sie gegen die
+/// Länge seiner eigenen Tabelle aus, die angezeigten Zeilennummern dagegen gelten für die
+/// CSV-Tabelle; beides kann voneinander abweichen
+pub fn segmente_im_kommando(rpnn_segmente: &[Segment], zeilen_string: &str) -> Vec<String> {
+    rpnn_segmente
+        .iter()
+        .map(Segment::to_string)
+        .filter(|segment| zeilen_string.split(',').any(|teil| teil == segment))
+        .collect()
+}
+
+/// Formatierte Zeilen für den Kommando-Aufruf, zusammenhängende Zeilen als Bereich (`1-5,10`)
+pub fn format_zeilen_fuer_kommando(zeilen_numbers: &[i32]) -> String {
+    zeilen_numbers.iter().copied().collect::<ZeilenSet>().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_vielfache_und_brueche_bleiben_im_kommando() {
+        let angabe = Zeilenangabe::parse("v5,2-3").unwrap();
+        let zeilen = ZeilenParser::zeilen_set(&angabe, 20).unwrap();
+        let vielfache: Vec<Segment> = angabe.vielfache().cloned().collect();
+        
+        assert_eq!(format_zeilenangabe_fuer_kommando(&vielfache, &zeilen, 20).unwrap(), "v5,2-3");
+        assert_eq!(segmente_im_kommando(&vielfache, "v5,2-3"), vec!["v5"]);
+        assert_eq!(format_zeilenangabe_fuer_kommando(&[], &zeilen, 20).unwrap(), "2-3,5,10,15,20");
+        assert_eq!(format_zeilen_fuer_kommando(&[10, 2, 3, 3, 5]), "2-3,5,10");
+        
+        // Ein `v`-Segment mit ausgeschlossenen Zeilen bleibt nicht erhalten
+        let angabe = Zeilenangabe::parse("v5,!10").unwrap();
+        let zeilen = ZeilenParser::zeilen_set(&angabe, 20).unwrap();
+        let vielfache: Vec<Segment> = angabe.vielfache().cloned().collect();
+        assert_eq!(format_zeilenangabe_fuer_kommando(&vielfache, &zeilen, 20).unwrap(), "5,15,20");
+        assert!(segmente_im_kommando(&vielfache, "5,15,20").is_empty());
+        
+        // Brüche bleiben wie `v` erhalten, gemischt mit Ganzzahlen
+        let angabe = Zeilenangabe::parse("1-5,1/2,10,3/4-1/1").unwrap();
+        let zeilen = ZeilenParser::zeilen_set(&angabe, 20).unwrap();
+        let segmente: Vec<Segment> = angabe.rpnn_segmente().cloned().collect();
+        assert_eq!(format_zeilenangabe_fuer_kommando(&segmente, &zeilen, 20).unwrap(), "1/2,3/4-1/1,1-5");
+        assert_eq!(segmente_im_kommando(&segmente, "1/2,3/4-1/1,1-5"), vec!["1/2", "3/4-1/1"]);
+        
+        let angabe = Zeilenangabe::parse("1/4-1/2,!7").unwrap();
+        let zeilen = ZeilenParser::zeilen_set(&angabe, 20).unwrap();
+        let segmente: Vec<Segment> = angabe.rpnn_segmente().cloned().collect();
+        assert_eq!(format_zeilenangabe_fuer_kommando(&segmente, &zeilen, 20).unwrap(), "5-6,8-10");
+    }
+    
+    #[test]
+    fn test_args_ohne_shell() {
+        // Leerzeichen und `;` bleiben Teil eines einzelnen Arguments
+        let optionen = KommandoOptionen::default();
+        let auswahl = SpaltenAuswahl::Name { first: "a b", second: "c;rm -rf x" };
+        assert_eq!(build_args(&optionen, "1-5", &auswahl), vec!["--vorhervonausschnitt", "1-5", "--spaltenname", "a b", "c;rm -rf x"]);
+        
+        let auswahl = SpaltenAuswahl::Nummern { flag: "--spalten", nummern: &[10, 5] };
+        assert_eq!(build_command(&optionen, "rpnn", "3", &auswahl), "rpnn --vorhervonausschnitt 3 --spalten 10,5");
+        
+        // Andere Flag-Schreibweisen und Zusatzargumente
+        let optionen = KommandoOptionen {
+            zeilen_flag: "-z".to_string(),
+            zusatz_args: vec!["--leise".to_string()],
+            ..KommandoOptionen::default()
+        };
+        assert_eq!(build_command(&optionen, "rpnn", "3", &auswahl), "rpnn --leise -z 3 --spalten 10,5");
+        
+        assert!(ausfuehren("rpnn-gibt-es-nicht", &[]).is_err());
+    }
+    
+    #[test]
+    fn test_shell_quoting() {
+        assert_eq!(shell_quote("1-5,v3"), "1-5,v3");
+        assert_eq!(shell_quote("Wirklichkeiten(10)"), "'Wirklichkeiten(10)'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+        
+        let auswahl = SpaltenAuswahl::Name { first: "warum_Transzendentalie_=_Strukturgroesse_=_Charakter", second: "a b" };
+        assert_eq!(
+            build_command(&KommandoOptionen::default(), "mein-rpnn", "1-5", &auswahl),
+            "mein-rpnn --vorhervonausschnitt 1-5 --spaltenname 'warum_Transzendentalie_=_Strukturgroesse_=_Charakter' 'a b'"
+        );
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_ausgabe_wird_aufgefangen() {
+        let args = ["-c".to_string(), "echo eins; echo zwei; echo kaputt >&2; exit 3".to_string()];
+        let ausfuehrung = ausfuehren("sh", &args).unwrap();
+        
+        assert_eq!(ausfuehrung.stdout, "eins\nzwei\n");
+        assert_eq!(ausfuehrung.stderr, "kaputt\n");
+        assert_eq!(ausfuehrung.zusammenfassung(), "Exit-Code 3 (Fehler), 2 Zeilen Ausgabe, 1 Zeilen Fehlerausgabe");
+        assert!(ausfuehrung.protokoll("sh -c …", "heute").starts_with("# Befehl: sh -c …\n# Ausgeführt: heute\n# Exit-Code 3"));
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_quoting_round_trip() {
+        // Die Shell zerlegt die gequotete Zeile wieder in genau die ursprünglichen Argumente
+        let args = ["mein-rpnn", "a b", "Wirklichkeiten(10)", "x;rm -rf /", "it's", "$HOME", "`id`", "*", "", "\\n", "ä=ö", "~"];
+        let ausgabe = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf '%s\\0' {}", shell_join(&args)))
+            .output()
+            .unwrap();
+        
+        let zurueck: Vec<String> = String::from_utf8(ausgabe.stdout)
+            .unwrap()
+            .split_terminator('\0')
+            .map(String::from)
+            .collect();
+        assert_eq!(zurueck, args);
+    }
+}
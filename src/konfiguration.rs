@@ -0,0 +1,128 @@
+// konfiguration.rs - Einstellungen für den rpnn-Aufruf aus einer JSON-Datei
+//
+// Reihenfolge: eingebaute Standardwerte, dann die Konfigurationsdatei, dann die Optionen der
+// Kommandozeile (`KommandoArgs::optionen` in cli.rs). Ist kein Programmpfad konfiguriert,
+// wird rpnn im PATH gesucht.
+//
+// Beispiel (`rpnn.json`):
+//
+//   { "kommando": { "programm": "/opt/rpnn/bin/mein-rpnn", "zeilen_flag": "--ausschnitt",
+//                   "zusatz_args": ["--farbe", "nie"] } }
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use crate::kommando::KommandoOptionen;
+
+/// Umgebungsvariable mit dem Pfad zur Konfigurationsdatei
+pub const CONFIG_ENV_VAR: &str = "RPNN_CONFIG";
+/// Dateiname, der im aktuellen Verzeichnis und unter `~/.config/rpnn/` gesucht wird
+pub const CONFIG_DATEI: &str = "rpnn.json";
+
+/// Inhalt der Konfigurationsdatei
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Konfiguration {
+    #[serde(default)]
+    pub kommando: KommandoKonfiguration,
+}
+
+/// Abschnitt `kommando`; fehlende Einträge behalten ihren Standardwert
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KommandoKonfiguration {
+    pub programm: Option<String>,
+    pub programm_name: Option<String>,
+    pub zeilen_flag: Option<String>,
+    pub spaltenname_flag: Option<String>,
+    pub spalten_flag: Option<String>,
+    pub zusatz_args: Option<Vec<String>>,
+}
+
+impl Konfiguration {
+    /// Sucht die Konfigurationsdatei: `--config <pfad>` vor `RPNN_CONFIG`, dann `rpnn.json`
+    /// im aktuellen Verzeichnis, dann `~/.config/rpnn/rpnn.json`
+    pub fn finde_datei(cli_path: Option<String>) -> Option<PathBuf> {
+        if let Some(path) = cli_path.or_else(|| std::env::var(CONFIG_ENV_VAR).ok()).filter(|p| !p.trim().is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        
+        let lokal = PathBuf::from(CONFIG_DATEI);
+        let benutzer = std::env::var_os("HOME").map(|home| Path::new(&home).join(".config/rpnn").join(CONFIG_DATEI));
+        std::iter::once(lokal).chain(benutzer).find(|path| path.is_file())
+    }
+    
+    /// Liest und prüft die Konfigurationsdatei
+    pub fn laden(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Konfigurationsdatei '{}' konnte nicht gelesen werden", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Ungültige Konfigurationsdatei '{}'", path.display()))
+    }
+    
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(serde_json::from_str(text)?)
+    }
+    
+    /// Übernimmt die gesetzten Einträge in die Kommando-Optionen
+    pub fn anwenden(self, optionen: &mut KommandoOptionen) {
+        let kommando = self.kommando;
+        let felder = [
+            (kommando.programm, &mut optionen.programm),
+            (kommando.programm_name, &mut optionen.programm_name),
+            (kommando.zeilen_flag, &mut optionen.zeilen_flag),
+            (kommando.spaltenname_flag, &mut optionen.spaltenname_flag),
+            (kommando.spalten_flag, &mut optionen.spalten_flag),
+        ];
+        for (wert, feld) in felder {
+            if let Some(wert) = wert {
+                *feld = wert;
+            }
+        }
+        if let Some(zusatz_args) = kommando.zusatz_args {
+            optionen.zusatz_args = zusatz_args;
+        }
+    }
+}
+
+/// Sucht ein ausführbares Programm in den Verzeichnissen von `PATH`
+pub fn finde_im_path(name: &str) -> Option<PathBuf> {
+    // Pfade mit Verzeichnisanteil werden nicht gesucht
+    if name.contains(std::path::MAIN_SEPARATOR) {
+        return None;
+    }
+    
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|verzeichnis| verzeichnis.join(name))
+        .find(|kandidat| ist_ausfuehrbar(kandidat))
+}
+
+#[cfg(unix)]
+fn ist_ausfuehrbar(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn ist_ausfuehrbar(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_konfiguration_anwenden() {
+        let konfiguration = Konfiguration::parse(r#"{ "kommando": { "zeilen_flag": "--ausschnitt", "zusatz_args": ["-q"] } }"#).unwrap();
+        let mut optionen = KommandoOptionen::default();
+        konfiguration.anwenden(&mut optionen);
+        
+        assert_eq!(optionen.zeilen_flag, "--ausschnitt");
+        assert_eq!(optionen.zusatz_args, vec!["-q"]);
+        assert_eq!(optionen.spaltenname_flag, KommandoOptionen::default().spaltenname_flag);
+        
+        // Tippfehler in Schlüsseln fallen auf
+        assert!(Konfiguration::parse(r#"{ "kommando": { "zeilenflag": "--x" } }"#).is_err());
+        assert!(finde_im_path("rpnn-gibt-es-nicht").is_none());
+    }
+}
@@ -0,0 +1,46 @@
+//! rpnn - Auswahl von Tabellenzeilen und Spalten für `mein-rpnn`
+//!
+//! Die Bibliothek lädt die zweistufige CSV-Tabelle (erste Spalte → zweite Spalte → Spaltennummern),
+//! beantwortet Abfragen zu Paaren, prüft und expandiert Zeilenangaben und baut daraus den
+//! rpnn-Befehl. Sie gibt selbst nichts aus; Warnungen und Ergebnisse werden als Daten geliefert.
+//!
+//! ```
+//! use rpnn::{CsvData, CsvQuelle, KommandoOptionen, SpaltenAuswahl, ZeilenParser};
+//! use rpnn::kommando::{build_command, format_zeilen_fuer_kommando};
+//!
+//! let csv_data = CsvData::load(&CsvQuelle::Eingebettet).unwrap();
+//! let first = &csv_data.all_first_columns[0].name;
+//! let second = &csv_data.get_seconds_for_first(first).unwrap()[0].name;
+//!
+//! assert!(rpnn::is_zeilen_angabe("1-5,10"));
+//! let zeilen = ZeilenParser::parse_to_numbers("1-5,10", csv_data.raw_data.len() as i32).unwrap();
+//! let (zeilen, _fehlend) = csv_data.split_existing_rows(&zeilen);
+//!
+//! let befehl = build_command(
+//!     &KommandoOptionen::default(),
+//!     "mein-rpnn",
+//!     &format_zeilen_fuer_kommando(&zeilen),
+//!     &SpaltenAuswahl::Name { first, second },
+//! );
+//! assert!(befehl.starts_with("mein-rpnn --vorhervonausschnitt 1-5,10"));
+//! ```
+
+pub mod ausgabe;
+pub mod autocomplete;
+pub mod befehlsbibliothek;
+mod csv_index;
+pub mod csv_parser;
+pub mod csv_data;
+pub mod kommando;
+pub mod konfiguration;
+pub mod normalisierung;
+pub mod zeilen_parser;
+pub mod zeilen_set;
+pub mod if_is_zeilen_angabe;
+
+pub use csv_data::{Aufloesung, CsvData};
+pub use csv_parser::{CsvParser, CsvQuelle, Eintrag, Spaltennummern};
+pub use if_is_zeilen_angabe::{is_zeilen_angabe, is_zeilen_bruch_angabe};
+pub use kommando::{KommandoOptionen, SpaltenAuswahl};
+pub use zeilen_parser::{FehlerArt, ParseFehler, ZeilenParser, Zeilenangabe};
+pub use zeilen_set::ZeilenSet;
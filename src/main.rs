@@ -1,24 +1,48 @@
-// main.rs - aktualisiert
-mod autocomplete;
-mod csv_parser;
-mod csv_data;
+// main.rs - Kommandozeile und Assistent; die eigentliche Logik liegt in der Bibliothek (lib.rs)
+mod cli;
 mod ui;
-mod zeilen_parser;  // Neue Modul-Deklaration
-mod if_is_zeilen_angabe;  // Falls noch nicht vorhanden
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use rpnn::{ausgabe, autocomplete, csv_parser};
 
 fn main() -> Result<()> {
-    // Optional: Test der Zeilenangabe-Validierung
+    // Mit Argumenten: nicht-interaktiver Modus ohne Eingabeaufforderungen
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (csv_pfad, args) = cli::take_option(&args, "--csv")?;
+    let quelle = csv_parser::CsvQuelle::resolve(csv_pfad);
+    
+    let (kommando_args, args) = cli::take_kommando_args(&args)?;
+    
+    let (vorschlaege, args) = cli::take_option(&args, "--vorschlaege")?;
+    let vorschlag_limit = match vorschlaege {
+        Some(n) => n.parse().with_context(|| format!("Ungültige Vorschlagsanzahl: '{}'", n))?,
+        None => autocomplete::DEFAULT_LIMIT,
+    };
+    
+    let (format, args) = cli::take_option(&args, "--format")?;
+    let format = match format {
+        Some(format) => ausgabe::Ausgabeformat::parse(&format)?,
+        None => ausgabe::Ausgabeformat::default(),
+    };
+    
+    if !args.is_empty() {
+        return cli::run(&args, &quelle, &kommando_args, format);
+    }
+    
+    // Optional: Test der Zeilenangabe-Validierung (nicht im JSON-Modus, stdout bleibt dem Dokument)
     #[cfg(debug_assertions)]
-    test_zeilen_validation();
+    if format == ausgabe::Ausgabeformat::Text {
+        test_zeilen_validation();
+    }
     
-    ui::run()
+    // Der Assistent erzeugt am Ende einen Befehl und braucht daher die Konfiguration
+    let optionen = kommando_args.optionen()?;
+    ui::run(&quelle, &optionen, vorschlag_limit, format)
 }
 
 #[cfg(debug_assertions)]
 fn test_zeilen_validation() {
-    use if_is_zeilen_angabe::*;
+    use rpnn::if_is_zeilen_angabe::*;
     
     println!("Testing Zeilenangabe Validation...");
     
@@ -28,12 +52,13 @@ fn test_zeilen_validation() {
         ("v1,v2-5", true),
         ("(1,3,5)", true),
         ("1/2-3/4", true),
+        ("1-5,1/2,10", true),
         ("abc", false),
         ("1.5,2", false),
     ];
     
     for (input, expected) in test_cases {
-        let result = is_zeilen_angabe(input);
+        let result = is_zeilen_bruch_or_ganz_zahl_angabe(input);
         let status = if result == expected { "✓" } else { "✗" };
         println!("{} '{}' -> {}", status, input, result);
     }
@@ -0,0 +1,43 @@
+// normalisierung.rs - Schreibweisen-unabhängiger Vergleich von Namen
+
+/// Faltet einen Namen auf eine Vergleichsform: Kleinschreibung, Umlaute und ß
+/// ausgeschrieben (ä → ae, ö → oe, ü → ue, ß → ss), übrige Akzente entfernt und
+/// Unterstriche wie Leerzeichen behandelt
+pub fn normalize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match c {
+            'ä' => result.push_str("ae"),
+            'ö' => result.push_str("oe"),
+            'ü' => result.push_str("ue"),
+            'ß' => result.push_str("ss"),
+            'á' | 'à' | 'â' | 'ã' | 'å' => result.push('a'),
+            'é' | 'è' | 'ê' | 'ë' => result.push('e'),
+            'í' | 'ì' | 'î' | 'ï' => result.push('i'),
+            'ó' | 'ò' | 'ô' | 'õ' => result.push('o'),
+            'ú' | 'ù' | 'û' => result.push('u'),
+            'ç' => result.push('c'),
+            'ñ' => result.push('n'),
+            '_' => result.push(' '),
+            _ => result.push(c),
+        }
+    }
+    
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_variants_fold_together() {
+        assert_eq!(normalize("Superkräfte"), normalize("Superkraefte"));
+        assert_eq!(normalize("höflich"), normalize("hoeflich"));
+        assert_eq!(normalize("strukturgröße"), normalize("strukturgroeße"));
+        assert_eq!(normalize("strukturgröße"), normalize("Strukturgroesse"));
+        assert_eq!(normalize("Satan_Teufel"), normalize("satan teufel"));
+        assert_ne!(normalize("Galaxie"), normalize("Galaxien"));
+    }
+}
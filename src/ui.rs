@@ -1,38 +1,84 @@
 // ui.rs - Vollständig korrigierte Version
-use std::process::Command;
-use crate::csv_data::CsvData;
+use crate::cli::print_json;
+use rpnn::ausgabe::{Ausgabeformat, PaarErgebnis};
+use rpnn::befehlsbibliothek::{Befehlsbibliothek, GespeicherterBefehl};
+use rpnn::csv_data::{Aufloesung, CsvData, format_csv_row, format_zahlen};
+use rpnn::csv_parser::CsvQuelle;
+use rpnn::kommando::{self, KommandoOptionen, SpaltenAuswahl};
+use rpnn::zeilen_parser::{ParseFehler, Segment, ZeilenParser, Zeilenangabe};
+use rpnn::zeilen_set::ZeilenSet;
 use inquire::Text;
-use anyhow::Result;
+use anyhow::{Result, bail};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-pub fn run() -> Result<()> {
-    println!("🔍 CSV Zwei-Stufen Autocomplete mit Zeilenangabe-Validierung\n");
+// Im JSON-Modus gehört stdout allein dem JSON-Dokument; die Texte des Assistenten gehen
+// dann auf stderr (die Eingabeaufforderungen von inquire stehen ohnehin dort)
+static TEXT_AUF_STDERR: AtomicBool = AtomicBool::new(false);
+
+macro_rules! textln {
+    ($($arg:tt)*) => {
+        if TEXT_AUF_STDERR.load(Ordering::Relaxed) { eprintln!($($arg)*) } else { println!($($arg)*) }
+    };
+}
+
+macro_rules! text {
+    ($($arg:tt)*) => {
+        if TEXT_AUF_STDERR.load(Ordering::Relaxed) { eprint!($($arg)*) } else { print!($($arg)*) }
+    };
+}
+
+// Leert den Strom, auf den `text!` schreibt (vor dem Lesen einer Eingabe)
+fn flush_text() {
+    use std::io::Write;
+    
+    let _ = if TEXT_AUF_STDERR.load(Ordering::Relaxed) {
+        std::io::stderr().flush()
+    } else {
+        std::io::stdout().flush()
+    };
+}
+
+pub fn run(
+    quelle: &CsvQuelle,
+    optionen: &KommandoOptionen,
+    vorschlag_limit: usize,
+    format: Ausgabeformat,
+) -> Result<()> {
+    TEXT_AUF_STDERR.store(format == Ausgabeformat::Json, Ordering::Relaxed);
+    
+    textln!("🔍 CSV Zwei-Stufen Autocomplete mit Zeilenangabe-Validierung\n");
     
     // CSV laden
-    let csv_data = CsvData::new();
+    let csv_data = load_csv_data(quelle)?;
     
     loop {
-        println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-        println!("┃ SCHRITT 1: Wählen Sie eine erste Spalte                                ┃");
-        println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+        textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+        textln!("┃ SCHRITT 1: Wählen Sie eine erste Spalte                                ┃");
+        textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
         
         // Autocomplete für erste Spalte
-        let first_autocomplete = csv_data.get_first_level_autocomplete();
-        let first_choice = Text::new("Erste Spalte auswählen:")
+        let first_autocomplete = csv_data.get_first_level_autocomplete().with_limit(vorschlag_limit);
+        let first_input = Text::new("Erste Spalte auswählen:")
             .with_autocomplete(first_autocomplete)
             .with_help_message("Beginnen Sie zu tippen für Vorschläge")
             .prompt()?;
         
-        println!("✅ Ausgewählt: '{}'", first_choice);
+        // Aliase und Vorschläge mit Alias-Hinweis auf den kanonischen Namen abbilden
+        let first_choice = csv_data.resolve_first(&first_input)
+            .map(str::to_string)
+            .unwrap_or(first_input);
+        
+        textln!("✅ Ausgewählt: '{}'", first_choice);
         
         // Zeige Details zu dieser ersten Spalte
-        csv_data.show_details_for_first(&first_choice);
+        show_details_for_first(&csv_data, &first_choice);
         
         // Überprüfe ob es zugehörige zweite Spalten gibt
         let seconds = match csv_data.get_seconds_for_first(&first_choice) {
             Some(seconds) if !seconds.is_empty() => seconds,
             _ => {
-                println!("\n⚠️  Keine zugehörigen zweiten Spalten für '{}'", first_choice);
-                println!("Möchten Sie eine andere erste Spalte wählen? (j/N)");
+                textln!("\n⚠️  Keine zugehörigen zweiten Spalten für '{}'", first_choice);
+                textln!("Möchten Sie eine andere erste Spalte wählen? (j/N)");
                 let again = Text::new("")
                     .with_default("n")
                     .prompt()?;
@@ -45,38 +91,57 @@ pub fn run() -> Result<()> {
             }
         };
         
-        println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-        println!("┃ SCHRITT 2: Wählen Sie eine zweite Spalte                                ┃");
-        println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
-        println!("Verfügbare Optionen für '{}':", first_choice);
+        textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+        textln!("┃ SCHRITT 2: Wählen Sie eine zweite Spalte                                ┃");
+        textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+        textln!("Verfügbare Optionen für '{}':", first_choice);
         
         // Autocomplete für zweite Spalte (abhängig von erster Wahl)
         let second_autocomplete = csv_data.get_second_level_autocomplete(&first_choice)
-            .expect("Sollte existieren da seconds vorhanden sind");
+            .expect("Sollte existieren da seconds vorhanden sind")
+            .with_limit(vorschlag_limit);
         
-        let second_choice = Text::new("Zweite Spalte auswählen:")
+        let second_input = Text::new("Zweite Spalte auswählen:")
             .with_autocomplete(second_autocomplete)
             .with_help_message(&format!("{} Optionen verfügbar", seconds.len()))
             .prompt()?;
         
-        println!("✅ Ausgewählt: '{}' → '{}'", first_choice, second_choice);
+        let second_choice = match csv_data.resolve_second(&first_choice, &second_input) {
+            Some(aufloesung) => {
+                if let Aufloesung::Mehrdeutig { name, eintraege } = &aufloesung {
+                    textln!("ℹ️  '{}' gehört zu mehreren Einträgen: {}", name, eintraege.join(", "));
+                    textln!("   Verwendet werden die Zeilen aller dieser Einträge.");
+                }
+                aufloesung.name().to_string()
+            }
+            None => second_input,
+        };
+        
+        textln!("✅ Ausgewählt: '{}' → '{}'", first_choice, second_choice);
         
         // Zeige vollständige Details zum Paar
-        csv_data.show_pair_details(&first_choice, &second_choice);
+        show_pair_details(&csv_data, &first_choice, &second_choice);
         
-        println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-        println!("┃ SCHRITT 3: Zeilenangabe eingeben und validieren                         ┃");
-        println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
-        println!("Beispiele für gültige Zeilenangaben:");
-        println!("  • Einzelne Zeilen: 1,2,3");
-        println!("  • Bereiche: 3-8,12");
-        println!("  • Mit v: v1, v2-5");
-        println!("  • Gemischt: 1-5,10,12-15");
-        println!("  • Generatoren: (1,3,5), [2,4,6]");
-        println!("  • Bruchangaben: 1/2, 3/4-5/6");
-        println!("  • Leer lassen für alle Zeilen");
+        textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+        textln!("┃ SCHRITT 3: Zeilenangabe eingeben und validieren                         ┃");
+        textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+        textln!("Beispiele für gültige Zeilenangaben:");
+        textln!("  • Einzelne Zeilen: 1,2,3");
+        textln!("  • Bereiche: 3-8,12");
+        textln!("  • Umgebung mit +: 5+2 (= 3,5,7), 3-8+2+5");
+        textln!("  • Vielfache mit v: v5 (= 5,10,15,…), v2-5");
+        textln!("  • Gemischt: 1-5,10,12-15 oder mit Brüchen 1-5,1/2,10");
+        textln!("  • Generatoren: (1,3,5), [2,4,6]");
+        textln!("  • Bruchangaben (Position in der Tabelle): 1/2, 3/4-5/6");
+        textln!("  • Offene Bereiche: -5 (Zeilen 1-5), 5- (ab Zeile 5); vom Ende gezählt: -5- (letzte fünf), 3--2");
+        textln!("  • Ausschließen mit ! oder \\: 1-50,!13,!20-25 oder 1-50 \\ 13");
+        textln!("  • Schnittmenge mit &: 1-100 & v2 (gerade Zeilen bis 100)");
+        textln!("  • Leer lassen für alle Zeilen");
         
-        let mut zeilen_history = Vec::new();
+        // Alle Eingaben zusammen ergeben die Vereinigung ihrer Zeilen
+        let mut zeilen_history = ZeilenSet::new();
+        // `v`-Segmente und Brüche bleiben im generierten Befehl erhalten
+        let mut rpnn_segmente_history: Vec<Segment> = Vec::new();
         
         loop {
             let zeilen_input = Text::new("Zeilenangabe eingeben (oder 'fertig' zum Beenden):")
@@ -95,13 +160,18 @@ pub fn run() -> Result<()> {
                 &csv_data
             );
             
-            if let Ok(zeilen_numbers) = result {
-                if !zeilen_numbers.is_empty() {
-                    zeilen_history.extend(zeilen_numbers);
+            if let Ok((zeilen, rpnn_segmente)) = result
+                && !zeilen.is_empty()
+            {
+                zeilen_history = zeilen_history.vereinigung(&zeilen);
+                for segment in rpnn_segmente {
+                    if !rpnn_segmente_history.contains(&segment) {
+                        rpnn_segmente_history.push(segment);
+                    }
                 }
             }
             
-            println!("\nWeitere Zeilenangabe eingeben? (j/N)");
+            textln!("\nWeitere Zeilenangabe eingeben? (j/N)");
             let again = Text::new("")
                 .with_default("n")
                 .prompt()?;
@@ -113,250 +183,266 @@ pub fn run() -> Result<()> {
         
         // Zeige Zusammenfassung
         if !zeilen_history.is_empty() {
-            show_ergebnis_zusammenfassung(&first_choice, &second_choice, &zeilen_history, &csv_data);
+            let ergebnis = show_ergebnis_zusammenfassung(
+                &first_choice,
+                &second_choice,
+                &zeilen_history,
+                &rpnn_segmente_history,
+                &csv_data,
+                optionen,
+            );
+            
+            // Im JSON-Modus zusätzlich das maschinenlesbare Ergebnis ausgeben (als einziges auf stdout)
+            if format == Ausgabeformat::Json
+                && let Some((rows, command)) = ergebnis
+            {
+                print_json(&PaarErgebnis::new(&csv_data, &first_choice, &second_choice, Some(&rows), Some(command)))?;
+            }
         }
         
-        println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-        println!("┃ Möchten Sie eine weitere Suche durchführen? (j/N)                       ┃");
-        println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+        textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+        textln!("┃ Möchten Sie eine weitere Suche durchführen? (j/N)                       ┃");
+        textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
         let again = Text::new("Weitersuchen?")
             .with_default("n")
             .prompt()?;
-            
+        
         if !again.to_lowercase().starts_with('j') {
             break;
         }
         
-        println!("\n🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄");
+        textln!("\n🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄🔄");
     }
     
-    println!("\n👋 Programm beendet.");
+    textln!("\n👋 Programm beendet.");
     
     Ok(())
 }
 
-// Funktion zur Validierung und Verarbeitung von Zeilenangaben
+// Lädt die Tabelle und zeigt Quelle, Warnungen und eine Übersicht an
+fn load_csv_data(quelle: &CsvQuelle) -> Result<CsvData> {
+    textln!("📂 Lade CSV-Daten aus {}...", quelle);
+    
+    let csv_data = CsvData::load(quelle)?;
+    
+    for warnung in &csv_data.warnungen {
+        eprintln!("⚠️  {}", warnung);
+    }
+    
+    textln!("✅ Quelle: {} ({} CSV-Zeilen)", quelle, csv_data.raw_data.len());
+    print_load_summary(&csv_data);
+    
+    Ok(csv_data)
+}
+
+// Zeigt eine Übersicht der geladenen Daten
+fn print_load_summary(csv_data: &CsvData) {
+    let sorted_first_to_seconds = &csv_data.first_to_seconds_map;
+    
+    textln!("✅ Geladen: {} verschiedene erste Spalten", csv_data.all_first_columns.len());
+    textln!("✅ Geladen: {} verschiedene Zuordnungen", sorted_first_to_seconds.len());
+    
+    // Zeige Beispiele
+    textln!("\n🔍 Beispiel-Zuordnungen:");
+    for (first, seconds) in sorted_first_to_seconds.iter().take(3) {
+        textln!("  '{}' → {} Optionen", first, seconds.len());
+        for (i, second) in seconds.iter().take(3).enumerate() {
+            textln!("     {}. {}", i + 1, second.name);
+        }
+        if seconds.len() > 3 {
+            textln!("     ... und {} weitere", seconds.len() - 3);
+        }
+    }
+    
+    if sorted_first_to_seconds.len() > 3 {
+        textln!("  ... und {} weitere Zuordnungen", sorted_first_to_seconds.len() - 3);
+    }
+}
+
+// Zeige alle Details für ein erstes Wort
+fn show_details_for_first(csv_data: &CsvData, first: &str) {
+    textln!("\n🔍 Details für '{}':", first);
+    
+    if let Some(seconds) = csv_data.get_seconds_for_first(first) {
+        textln!("  📋 Verfügbare zweite Spalten ({}):", seconds.len());
+        for (i, second) in seconds.iter().enumerate().take(10) {
+            text!("    {:2}. {}", i + 1, second.name);
+            
+            // Zeige zugehörige Nummern
+            let numbers = csv_data.find_column_numbers_for_pair(first, &second.name);
+            if !numbers.is_empty() {
+                text!(" → {}", format_zahlen(&numbers));
+            }
+            textln!();
+        }
+        
+        if seconds.len() > 10 {
+            textln!("    ... und {} weitere", seconds.len() - 10);
+        }
+    } else {
+        textln!("  ⚠️  Keine zugehörigen zweiten Spalten gefunden");
+    }
+}
+
+// Zeige vollständige Informationen zu einem Paar
+fn show_pair_details(csv_data: &CsvData, first: &str, second: &str) {
+    textln!("\n🔍 Vollständige Informationen:");
+    textln!("  Erste Spalte:  {}", first);
+    textln!("  Zweite Spalte: {}", second);
+    
+    let numbers = csv_data.find_column_numbers_for_pair(first, second);
+    if !numbers.is_empty() {
+        textln!("  Zugehörige Nummern: {}", format_zahlen(&numbers));
+    } else {
+        textln!("  ℹ️  Keine zugehörigen Nummern gefunden");
+    }
+    
+    // Zeige alle CSV-Zeilen mit diesem Paar
+    textln!("\n  📄 CSV-Zeilen mit diesem Paar:");
+    let rows = csv_data.get_row_numbers_for_pair(first, second);
+    for &row_num in &rows {
+        let (first_entry, second_entries, numbers) = &csv_data.raw_data[(row_num - 1) as usize];
+        textln!("    Zeile {}: {}", row_num, format_csv_row(first_entry, second_entries, numbers));
+    }
+    
+    if rows.is_empty() {
+        textln!("    ⚠️ Keine direkten Einträge gefunden");
+    }
+}
+
+// Funktion zur Validierung und Verarbeitung von Zeilenangaben;
+// liefert die existierenden Zeilen sowie die `v`-Segmente und Brüche der Eingabe
 fn validate_and_process_zeilenangabe(
     input: &str,
     first: &str,
     second: &str,
     csv_data: &CsvData,
-) -> Result<Vec<i32>, String> {
+) -> Result<(ZeilenSet, Vec<Segment>), String> {
     let trimmed = input.trim();
     
-    println!("\n🔎 Validierung der Zeilenangabe '{}'...", trimmed);
+    textln!("\n🔎 Validierung der Zeilenangabe '{}'...", trimmed);
     
     // Wenn leer, alle Zeilen nehmen
     if trimmed.is_empty() {
-        println!("✅ Alle Zeilen werden ausgewählt");
+        textln!("✅ Alle Zeilen werden ausgewählt");
         let all_rows = get_all_row_numbers_for_pair(first, second, csv_data);
         show_selected_rows(first, second, &all_rows, csv_data);
-        return Ok(all_rows);
+        return Ok((all_rows.into_iter().collect(), Vec::new()));
     }
     
-    // Prüfe verschiedene Formate
-    if crate::if_is_zeilen_angabe::is_zeilen_angabe(trimmed) {
-        println!("✅ Gültige Zeilenangabe erkannt!");
-        
-        // Zeilen in einzelne Zahlen umwandeln
-        if let Some(zeilen_numbers) = parse_zeilen_angabe_to_numbers(trimmed) {
-            println!("✅ Extrahierte Zeilennummern: {:?}", zeilen_numbers);
-            
-            // Überprüfe, ob die Zeilen existieren
-            let valid_rows = validate_row_numbers(&zeilen_numbers, csv_data);
-            
-            // Zeige die entsprechenden CSV-Zeilen an
-            show_selected_rows(first, second, &valid_rows, csv_data);
-            
-            Ok(valid_rows)
-        } else {
-            let err = "Konnte Zeilenangabe nicht parsen".to_string();
-            println!("⚠️ {}", err);
-            Err(err)
-        }
-    } else if crate::if_is_zeilen_angabe::is_zeilen_bruch_angabe(trimmed) {
-        println!("✅ Gültige Bruchangabe erkannt!");
-        println!("✅ Bruchangabe: {}", trimmed);
-        
-        // Hier könnten Sie spezielle Bruch-Verarbeitung implementieren
-        process_bruch_angabe(trimmed, first, second, csv_data);
-        Ok(Vec::new())
+    // Einmal parsen; Prüfung und Expansion verwenden denselben Syntaxbaum
+    let angabe = match Zeilenangabe::parse(trimmed) {
+        Ok(angabe) => angabe,
+        Err(fehler) => return Err(show_invalid_input(trimmed, &fehler)),
+    };
+    
+    // Ganzzahlen und Brüche dürfen gemischt werden (wie is_zeilen_bruch_or_ganz_zahl_angabe)
+    if angabe.ist_ganzzahl_angabe() {
+        textln!("✅ Gültige Zeilenangabe erkannt!");
+    } else if angabe.ist_bruch_angabe() {
+        textln!("✅ Gültige Bruchangabe erkannt!");
     } else {
-        let err = format!("Ungültige Eingabe: '{}'", trimmed);
-        println!("⚠️ {}", err);
-        println!("  Erlaubte Formate:");
-        println!("    - Einzelne Zahlen: 1,2,3");
-        println!("    - Bereiche: 1-5,10-15");
-        println!("    - Mit 'v': v1, v2-5");
-        println!("    - Generatoren: (1,3,5), [2,4,6]");
-        println!("    - Brüche: 1/2, 3/4-5/6");
-        Err(err)
+        textln!("✅ Gültige gemischte Zeilen- und Bruchangabe erkannt!");
     }
-}
-
-// Parst eine Zeilenangabe in eine Liste von Zahlen
-fn parse_zeilen_angabe_to_numbers(input: &str) -> Option<Vec<i32>> {
-    use crate::if_is_zeilen_angabe::split;
-    
-    let parts = split::split_with_bracket_balance(input);
-    let mut numbers = Vec::new();
-    
-    for part in parts {
-        if part.is_empty() {
-            continue;
-        }
-        
-        // Entferne führendes 'v' falls vorhanden
-        let clean_part = if part.starts_with('v') || part.starts_with('V') {
-            &part[1..]
-        } else {
-            part
-        };
-        
-        // Prüfe auf Generator-Notation
-        if let Some(generator_nums) = crate::if_is_zeilen_angabe::str_as_generator_to_list_of_num_strs(clean_part) {
-            for num_str in generator_nums {
-                if let Ok(num) = num_str.parse::<i32>() {
-                    numbers.push(num);
-                }
-            }
-            continue;
-        }
-        
-        // Prüfe auf Generator-Notation mit führendem Zeichen
-        if part.len() > 1 {
-            let first_char = part.chars().next().unwrap();
-            if !first_char.is_ascii_digit() && first_char != '-' && first_char != 'v' && first_char != 'V' {
-                if let Some(generator_nums) = crate::if_is_zeilen_angabe::str_as_generator_to_list_of_num_strs(&part[1..]) {
-                    for num_str in generator_nums {
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            numbers.push(num);
-                        }
-                    }
-                    continue;
-                }
-            }
-        }
-        
-        // Prüfe auf Bereich (z.B. "3-8")
-        if let Some((start_str, end_str)) = clean_part.split_once('-') {
-            if let (Ok(start), Ok(end)) = (start_str.parse::<i32>(), end_str.parse::<i32>()) {
-                for i in start..=end {
-                    numbers.push(i);
-                }
-                continue;
-            }
-        }
-        
-        // Einzelne Zahl
-        if let Ok(num) = clean_part.parse::<i32>() {
-            numbers.push(num);
-        }
+    if angabe.hat_mengenoperationen() {
+        textln!("  Mit Mengenoperationen (!, &, \\); der Befehl enthält die aufgelösten Zeilen");
     }
     
-    // Duplikate entfernen und sortieren
-    numbers.sort();
-    numbers.dedup();
+    // Zeilen in einzelne Zahlen umwandeln; relative Segmente zeigen zusätzlich ihre Zuordnung
+    let zeilen = process_relative_angabe(&angabe, csv_data).map_err(|err| {
+        textln!("⚠️ {}", err);
+        err.to_string()
+    })?;
+    textln!("✅ Extrahierte Zeilennummern: {}", zeilen);
+    
+    // Zeige die entsprechenden CSV-Zeilen an
+    show_selected_rows(first, second, &zeilen.zeilen(csv_data.raw_data.len() as i32), csv_data);
     
-    Some(numbers)
+    Ok((zeilen, angabe.rpnn_segmente().cloned().collect()))
 }
 
-// Verarbeitet Bruchangaben
-fn process_bruch_angabe(input: &str, first: &str, second: &str, _csv_data: &CsvData) {
-    println!("ℹ️  Bruchangabe-Verarbeitung für '{}' → '{}'", first, second);
-    println!("Bruch: {}", input);
-    
-    // Einfache Implementierung - kann erweitert werden
-    let parts: Vec<&str> = input.split(',').collect();
-    for part in parts {
-        if part.contains('/') {
-            println!("  Bruch '{}' erkannt", part);
-        }
+// Meldet eine ungültige Zeilenangabe mit der Fehlerstelle und den erlaubten Formaten
+fn show_invalid_input(trimmed: &str, fehler: &ParseFehler) -> String {
+    let err = format!("Ungültige Eingabe: '{}' ({})", trimmed, fehler);
+    textln!("⚠️ Ungültige Eingabe:");
+    for zeile in fehler.anzeigen(trimmed).lines() {
+        textln!("    {}", zeile);
     }
+    textln!("  Erlaubte Formate:");
+    textln!("    - Einzelne Zahlen: 1,2,3");
+    textln!("    - Bereiche: 1-5,10-15");
+    textln!("    - Umgebung mit +: 5+2 (= 3,5,7)");
+    textln!("    - Vielfache mit 'v': v5, v2-5");
+    textln!("    - Generatoren: (1,3,5), [2,4,6]");
+    textln!("    - Brüche (Position in der Tabelle): 1/2, 3/4-5/6");
+    textln!("    - Offene Bereiche: -5 (Zeilen 1-5), 5- (ab Zeile 5); vom Ende gezählt: -5- (letzte fünf), 3--2");
+    textln!("    - Mengenoperationen: 1-50,!13 (ohne), 1-50 \\ 13 (ohne), 1-100 & v2 (Schnitt)");
+    err
 }
 
-// Holt alle Zeilennummern für ein Paar
-fn get_all_row_numbers_for_pair(first: &str, second: &str, csv_data: &CsvData) -> Vec<i32> {
-    let mut row_numbers = Vec::new();
-    
-    for (i, (first_cols, second_cols, _)) in csv_data.raw_data.iter().enumerate() {
-        if first_cols.contains(&first.to_string()) && second_cols.contains(&second.to_string()) {
-            row_numbers.push((i + 1) as i32); // 1-basierte Zeilennummern
+// Expandiert die Angabe; Brüche, negative Zahlen, offene Enden und `v` beziehen sich auf
+// die Tabellenlänge, daher wird ihre Auflösung einzeln angezeigt. Zeilen außerhalb der
+// Tabelle werden zusammengefasst gemeldet und fallen weg; bleibt keine übrig, ist das ein Fehler.
+fn process_relative_angabe(angabe: &Zeilenangabe, csv_data: &CsvData) -> Result<ZeilenSet> {
+    let max_zeile = csv_data.raw_data.len() as i32;
+    
+    for (segment, zeilen) in ZeilenParser::aufloesungen(angabe, max_zeile)? {
+        if zeilen.is_empty() {
+            textln!("  '{}' bei {} Zeilen → keine Zeilen", segment, max_zeile);
+        } else {
+            textln!("  '{}' bei {} Zeilen → Zeilen {}", segment, max_zeile, zeilen);
         }
     }
     
-    row_numbers
-}
-
-// Validiert Zeilennummern gegen CSV-Daten
-fn validate_row_numbers(zeilen_numbers: &[i32], csv_data: &CsvData) -> Vec<i32> {
-    let max_row = csv_data.raw_data.len() as i32;
-    let mut valid_rows = Vec::new();
-    
-    for &row_num in zeilen_numbers {
-        if row_num >= 1 && row_num <= max_row {
-            valid_rows.push(row_num);
-        } else {
-            println!("⚠️  Zeile {} existiert nicht (max: {})", row_num, max_row);
-        }
+    let zeilen = ZeilenParser::zeilen_set(angabe, max_zeile)?;
+    let ausserhalb = zeilen.ausserhalb(1, i64::from(max_zeile));
+    if !ausserhalb.is_empty() {
+        textln!("⚠️  {} Zeilen außerhalb der Tabelle (1-{}) werden ignoriert: {}", ausserhalb.anzahl(), max_zeile, ausserhalb.kurz(10));
     }
     
-    valid_rows
+    let zeilen = zeilen.begrenzt(1, i64::from(max_zeile));
+    if zeilen.is_empty() {
+        bail!("Zeilenangabe '{}' wählt keine existierenden Zeilen aus", angabe);
+    }
+    Ok(zeilen)
+}
+
+// Holt alle Zeilennummern für ein Paar
+fn get_all_row_numbers_for_pair(first: &str, second: &str, csv_data: &CsvData) -> Vec<i32> {
+    csv_data.get_row_numbers_for_pair(first, second)
 }
 
 // Zeigt ausgewählte Zeilen an
 fn show_selected_rows(first: &str, second: &str, zeilen_numbers: &[i32], csv_data: &CsvData) {
     if zeilen_numbers.is_empty() {
-        println!("ℹ️  Keine Zeilen ausgewählt");
+        textln!("ℹ️  Keine Zeilen ausgewählt");
         return;
     }
     
-    println!("\n📋 Ausgewählte CSV-Zeilen für '{}' → '{}':", first, second);
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    textln!("\n📋 Ausgewählte CSV-Zeilen für '{}' → '{}':", first, second);
+    textln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     
     let mut matching_rows = 0;
     let mut total_shown = 0;
     
     for &row_num in zeilen_numbers {
-        let index = (row_num - 1) as usize; // 1-basiert zu 0-basiert
-        
-        if index < csv_data.raw_data.len() {
-            let (first_cols, second_cols, numbers) = &csv_data.raw_data[index];
+        if csv_data.row_matches_pair(row_num, first, second) {
+            let (first_entry, second_entries, numbers) = &csv_data.raw_data[(row_num - 1) as usize];
+            matching_rows += 1;
+            total_shown += 1;
             
-            if first_cols.contains(&first.to_string()) && second_cols.contains(&second.to_string()) {
-                matching_rows += 1;
-                total_shown += 1;
-                
-                if total_shown <= 20 { // Begrenze die Ausgabe
-                    println!("Zeile {:3}: {}", row_num, format_csv_row(first_cols, second_cols, numbers));
-                }
+            if total_shown <= 20 { // Begrenze die Ausgabe
+                textln!("Zeile {:3}: {}", row_num, format_csv_row(first_entry, second_entries, numbers));
             }
         }
     }
     
     if total_shown > 20 {
-        println!("... und {} weitere Zeilen", total_shown - 20);
+        textln!("... und {} weitere Zeilen", total_shown - 20);
     }
     
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Gesamt: {} von {} ausgewählten Zeilen passen", matching_rows, zeilen_numbers.len());
-}
-
-// Formatiert eine CSV-Zeile für die Ausgabe
-fn format_csv_row(first_cols: &[String], second_cols: &[String], numbers: &str) -> String {
-    let first_fmt = if first_cols.len() == 1 {
-        first_cols[0].clone()
-    } else {
-        format!("({})", first_cols.join(", "))
-    };
-    
-    let second_fmt = if second_cols.len() == 1 {
-        second_cols[0].clone()
-    } else {
-        format!("({})", second_cols.join(", "))
-    };
-    
-    format!("{} → {} → {}", first_fmt, second_fmt, numbers)
+    textln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    textln!("Gesamt: {} von {} ausgewählten Zeilen passen", matching_rows, zeilen_numbers.len());
 }
 
 // Zeigt eine Ergebnis-Zusammenfassung
@@ -364,124 +450,128 @@ fn format_csv_row(first_cols: &[String], second_cols: &[String], numbers: &str)
 fn show_ergebnis_zusammenfassung(
     first: &str,
     second: &str,
-    zeilen_history: &[i32],
+    zeilen_history: &ZeilenSet,
+    rpnn_segmente: &[Segment],
     csv_data: &CsvData,
-) {
-    println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-    println!("┃ 📊 ERGEBNIS-ZUSAMMENFASSUNG                                           ┃");
-    println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
-    
-    // Sortiere und entferne Duplikate
-    let mut sorted_rows = zeilen_history.to_vec();
-    sorted_rows.sort();
-    sorted_rows.dedup();
-    
-    println!("Paar: '{}' → '{}'", first, second);
-    println!("Ausgewählte Zeilen: {}", sorted_rows.len());
-    
-    if !sorted_rows.is_empty() {
-        if sorted_rows.len() <= 10 {
-            println!("Zeilennummern: {:?}", sorted_rows);
-        } else {
-            println!("Zeilennummern: {:?} ... und {} weitere", 
-                &sorted_rows[..10], sorted_rows.len() - 10);
-        }
-        
-        // Zeige Statistiken
-        let total_matching = count_matching_rows(first, second, &sorted_rows, csv_data);
-        println!("Davon passende Zeilen: {}", total_matching);
-        
-        // GENERIERE UND ZEIGE DEN KOMMANDO-STRING
-        generate_and_show_command_string(first, second, &sorted_rows);
+    optionen: &KommandoOptionen,
+) -> Option<(Vec<i32>, String)> {
+    textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+    textln!("┃ 📊 ERGEBNIS-ZUSAMMENFASSUNG                                           ┃");
+    textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+    
+    textln!("Paar: '{}' → '{}'", first, second);
+    textln!("Ausgewählte Zeilen: {}", zeilen_history.anzahl());
+    
+    if zeilen_history.is_empty() {
+        return None;
     }
+    
+    textln!("Zeilennummern: {}", zeilen_history.kurz(10));
+    
+    // Zeige Statistiken
+    let max_zeile = csv_data.raw_data.len() as i32;
+    let rows = zeilen_history.zeilen(max_zeile);
+    let total_matching = count_matching_rows(first, second, &rows, csv_data);
+    textln!("Davon passende Zeilen: {}", total_matching);
+    
+    // Zeilen für den Befehl: `v`-Segmente und Brüche wie eingegeben, der Rest als Bereiche
+    let zeilen_string = kommando::format_zeilenangabe_fuer_kommando(rpnn_segmente, zeilen_history, max_zeile)
+        .unwrap_or_else(|_| zeilen_history.to_string());
+    let behalten = kommando::segmente_im_kommando(rpnn_segmente, &zeilen_string);
+    if !behalten.is_empty() {
+        textln!("ℹ️  {} wertet rpnn gegen die Länge seiner eigenen Tabelle aus; die Zeilennummern", behalten.join(", "));
+        textln!("   oben gelten für die {} Zeilen dieser CSV-Tabelle.", max_zeile);
+    }
+    
+    // GENERIERE UND ZEIGE DEN KOMMANDO-STRING
+    let spalten_nummern = csv_data.find_column_numbers_for_pair(first, second);
+    let command = generate_and_show_command_string(first, second, &spalten_nummern, &zeilen_string, optionen);
+    
+    Some((rows, command))
 }
 
 // Neue Funktion: Generiert und zeigt den Kommando-String
-fn generate_and_show_command_string(first: &str, second: &str, zeilen_numbers: &[i32]) {
-    println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-    println!("┃ 🚀 GENERIERTER KOMMANDO-AUFRUF                                        ┃");
-    println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+fn generate_and_show_command_string(
+    first: &str,
+    second: &str,
+    spalten_nummern: &[u32],
+    zeilen_string: &str,
+    optionen: &KommandoOptionen,
+) -> String {
+    textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+    textln!("┃ 🚀 GENERIERTER KOMMANDO-AUFRUF                                        ┃");
+    textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
     
-    // 1. Konvertiere Zeilennummern in das benötigte Format
-    let zeilen_string = format_zeilen_fuer_kommando(zeilen_numbers);
+    // 1. Spalten über den Namen oder (falls vorhanden) über die Nummern auswählen
+    let auswahl = choose_spalten_auswahl(first, second, spalten_nummern, optionen);
     
     // 2. Baue den Kommando-String
-    let command = format!(
-        "../target/debug/mein-rpnn --vorhervonausschnitt {} --spaltenname {} {}",
-        zeilen_string, first, second
-    );
+    let command = kommando::build_command(optionen, &optionen.programm, zeilen_string, &auswahl);
     
-    println!("📋 Vollständiger Befehl:");
-    println!("{}", command);
+    textln!("📋 Vollständiger Befehl:");
+    textln!("{}", command);
     
     // 3. Kopierbare Version (ohne Pfad für einfachere Nutzung)
-    let simplified_command = format!(
-        "mein-rpnn --vorhervonausschnitt {} --spaltenname {} {}",
-        zeilen_string, first, second
-    );
+    let simplified_command = kommando::build_command(optionen, &optionen.programm_name, zeilen_string, &auswahl);
+    
+    textln!("\n📝 Vereinfachte Version (zum Kopieren):");
+    textln!("{}", simplified_command);
     
-    println!("\n📝 Vereinfachte Version (zum Kopieren):");
-    println!("{}", simplified_command);
+    // 4. Option zum Kopieren in Zwischenablage (falls unterstützt); zum Ausführen
+    //    werden die Argumente einzeln übergeben, nicht der String
+    let args = kommando::build_args(optionen, zeilen_string, &auswahl);
+    offer_copy_option(&command, &optionen.programm, &args);
     
-    // 4. Option zum Kopieren in Zwischenablage (falls unterstützt)
-    offer_copy_option(&command);
+    command
 }
 
-// Formatierte Zeilen für den Kommando-Aufruf
-fn format_zeilen_fuer_kommando(zeilen_numbers: &[i32]) -> String {
-    if zeilen_numbers.is_empty() {
-        return String::new();
+// Fragt, ob der Befehl die Spalten über Namen oder Nummern auswählen soll
+fn choose_spalten_auswahl<'a>(
+    first: &'a str,
+    second: &'a str,
+    spalten_nummern: &'a [u32],
+    optionen: &'a KommandoOptionen,
+) -> SpaltenAuswahl<'a> {
+    use std::io;
+    
+    let nach_name = SpaltenAuswahl::Name { first, second };
+    if spalten_nummern.is_empty() {
+        return nach_name;
     }
     
-    // Sortiere die Zahlen
-    let mut sorted = zeilen_numbers.to_vec();
-    sorted.sort();
+    textln!("Zugehörige Spaltennummern: {}", kommando::format_spalten_fuer_kommando(spalten_nummern));
+    text!("Spalten über Nummern ({}) statt über den Namen auswählen? (j/N): ", optionen.spalten_flag);
+    flush_text();
     
-    // Gruppiere zusammenhängende Bereiche
-    let mut result = String::new();
-    let mut start = sorted[0];
-    let mut prev = sorted[0];
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap_or(0);
     
-    for i in 1..sorted.len() {
-        if sorted[i] == prev + 1 {
-            // Fortlaufender Bereich
-            prev = sorted[i];
-        } else {
-            // Bereich beenden und neuen starten
-            if start == prev {
-                result.push_str(&format!("{},", start));
-            } else {
-                result.push_str(&format!("{}-{},", start, prev));
-            }
-            start = sorted[i];
-            prev = sorted[i];
+    if input.trim().to_lowercase().starts_with('j') {
+        SpaltenAuswahl::Nummern {
+            flag: &optionen.spalten_flag,
+            nummern: spalten_nummern,
         }
-    }
-    
-    // Letzten Bereich hinzufügen
-    if start == prev {
-        result.push_str(&format!("{}", start));
     } else {
-        result.push_str(&format!("{}-{}", start, prev));
+        nach_name
     }
-    
-    result
 }
 
 // Bietet Option zum Kopieren an
-fn offer_copy_option(command: &str) {
-    use std::io::{self, Write};
+fn offer_copy_option(command: &str, programm: &str, args: &[String]) {
+    use std::io;
     
-    println!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
-    println!("┃ 📋 KOPIER-OPTIONEN                                                    ┃");
-    println!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
+    textln!("\n┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓");
+    textln!("┃ 📋 KOPIER-OPTIONEN                                                    ┃");
+    textln!("┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛");
     
-    println!("1. Den obigen Befehl manuell kopieren");
-    println!("2. Befehl in Datei speichern");
-    println!("3. Direkt ausführen (experimentell)");
+    textln!("1. Den obigen Befehl manuell kopieren");
+    textln!("2. Befehl in Skriptdatei speichern (anhängen)");
+    textln!("3. Direkt ausführen und Ausgabe anzeigen");
+    textln!("4. In Befehlsbibliothek speichern");
+    textln!("5. Befehlsbibliothek verwalten");
     
-    print!("\nIhre Wahl (1-3, Enter für keine Aktion): ");
-    io::stdout().flush().unwrap();
+    text!("\nIhre Wahl (1-5, Enter für keine Aktion): ");
+    flush_text();
     
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap_or(0);
@@ -489,82 +579,263 @@ fn offer_copy_option(command: &str) {
     
     match choice {
         "2" => save_command_to_file(command),
-        "3" => execute_command_experimental(command),
-        _ => println!("ℹ️  Befehl kann manuell kopiert werden."),
+        "3" => execute_command(command, programm, args),
+        "4" => save_to_bibliothek(command, programm, args),
+        "5" => manage_bibliothek(),
+        _ => textln!("ℹ️  Befehl kann manuell kopiert werden."),
     }
 }
 
-// Speichert den Befehl in eine Datei
+// Aktueller Zeitpunkt für Kommentare und Protokolle
+fn zeitstempel() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// Hängt den Befehl mit Zeitstempel an eine Skriptdatei an (oder überschreibt sie auf Wunsch)
 fn save_command_to_file(command: &str) {
-    use std::fs::File;
-    use std::io::Write;
-    
-    let filename = "generated_command.sh";
+    let filename = match Text::new("Skriptdatei:").with_default("generated_command.sh").prompt() {
+        Ok(filename) if !filename.trim().is_empty() => filename.trim().to_string(),
+        _ => return,
+    };
     
-    match File::create(filename) {
-        Ok(mut file) => {
-            // Unix/Linux Shell-Skript
-            writeln!(file, "#!/bin/bash").unwrap();
-            writeln!(file, "# Generierter Befehl").unwrap();
-            writeln!(file, "{}", command).unwrap();
-            
-            // Machen Sie es ausführbar (Unix)
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let mut perms = file.metadata().unwrap().permissions();
-                perms.set_mode(0o755);
-                std::fs::set_permissions(filename, perms).unwrap();
+    let mut anhaengen = false;
+    if std::path::Path::new(&filename).exists() {
+        let antwort = Text::new("Datei existiert bereits. (a)nhängen oder (u)eberschreiben?")
+            .with_default("a")
+            .prompt()
+            .unwrap_or_default();
+        match antwort.trim().to_lowercase().chars().next() {
+            Some('a') => anhaengen = true,
+            Some('u') | Some('ü') => {}
+            _ => {
+                textln!("ℹ️  Nicht gespeichert.");
+                return;
             }
-            
-            println!("✅ Befehl gespeichert in: {}", filename);
-            println!("   Ausführen mit: bash {}", filename);
         }
-        Err(e) => {
-            println!("⚠️  Fehler beim Speichern: {}", e);
+    }
+    
+    match write_command_script(&filename, command, anhaengen) {
+        Ok(()) => {
+            let aktion = if anhaengen { "angehängt an" } else { "gespeichert in" };
+            textln!("✅ Befehl {}: {}", aktion, filename);
+            textln!("   Ausführen mit: bash {}", filename);
         }
+        Err(e) => textln!("⚠️  Fehler beim Speichern: {}", e),
     }
 }
 
-// Versucht den Befehl auszuführen (experimentell)
-fn execute_command_experimental(command: &str) {
-    println!("⚠️  EXPERIMENTELL: Versuche Befehl auszuführen...");
+// Schreibt den Befehl; eine neue Datei bekommt zuerst die Shebang-Zeile
+fn write_command_script(filename: &str, command: &str, anhaengen: bool) -> std::io::Result<()> {
+    use std::fs::OpenOptions;
+    use std::io::Write;
     
-    // Entferne den relativen Pfad für die Ausführung
-    let cmd_without_path = command.replace("../target/debug/", "");
+    let mut optionen = OpenOptions::new();
+    optionen.create(true).write(true).append(anhaengen).truncate(!anhaengen);
     
-    println!("Ausführe: {}", cmd_without_path);
-
-    let status = Command::new("zsh")
-        .arg("-c")
-        .arg(cmd_without_path)
-        .status()
-        .expect("sh konnte nicht gestartet werden");
+    // Eine neue Datei wird ausführbar angelegt (Unix); die Rechte einer bestehenden bleiben
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        optionen.mode(0o755);
+    }
+    
+    let mut file = optionen.open(filename)?;
+    
+    if !anhaengen {
+        writeln!(file, "#!/bin/bash")?;
+    }
+    // Die Argumente sind bereits für die Shell gequotet (kommando::shell_join)
+    writeln!(file, "\n# Generierter Befehl vom {}", zeitstempel())?;
+    writeln!(file, "{}", command)?;
+    
+    Ok(())
+}
 
-    println!("Exit-Code: {}", status);
+// Lädt die Befehlsbibliothek; Fehler werden gemeldet
+fn load_bibliothek(path: &std::path::Path) -> Option<Befehlsbibliothek> {
+    match Befehlsbibliothek::laden(path) {
+        Ok(bibliothek) => Some(bibliothek),
+        Err(err) => {
+            textln!("⚠️  {:#}", err);
+            None
+        }
+    }
+}
 
+// Speichert Programm und Argumente unter einem Namen in der Befehlsbibliothek
+fn save_to_bibliothek(command: &str, programm: &str, args: &[String]) {
+    let path = Befehlsbibliothek::standard_pfad();
+    let Some(mut bibliothek) = load_bibliothek(&path) else { return };
+    
+    let name = match Text::new("Name für den Befehl:").prompt() {
+        Ok(name) => name.trim().to_string(),
+        Err(_) => return,
+    };
     
-    // ACHTUNG: Dies ist nur ein Beispiel - in der Praxis möchten Sie
-    // wahrscheinlich den Benutzer fragen, bevor Sie etwas ausführen
-    //println!("ℹ️  Ausführung deaktiviert (Sicherheitsfeature)");
-    println!("   Befehl kann manuell ausgeführt werden.");
+    let eintrag = GespeicherterBefehl {
+        name: name.clone(),
+        befehl: command.to_string(),
+        programm: programm.to_string(),
+        args: args.to_vec(),
+        gespeichert: zeitstempel(),
+    };
+    
+    match bibliothek.hinzufuegen(eintrag).and_then(|()| bibliothek.speichern(&path)) {
+        Ok(()) => textln!("✅ '{}' gespeichert in: {}", name, path.display()),
+        Err(err) => textln!("⚠️  {:#}", err),
+    }
 }
 
-// Zählt passende Zeilen
-fn count_matching_rows(first: &str, second: &str, zeilen_numbers: &[i32], csv_data: &CsvData) -> i32 {
-    let mut count = 0;
+// Listet die gespeicherten Befehle; ein Eintrag kann erneut ausgeführt oder gelöscht werden
+fn manage_bibliothek() {
+    let path = Befehlsbibliothek::standard_pfad();
+    let Some(mut bibliothek) = load_bibliothek(&path) else { return };
     
-    for &row_num in zeilen_numbers {
-        let index = (row_num - 1) as usize;
+    loop {
+        textln!("\n📚 Befehlsbibliothek ({})", path.display());
+        if bibliothek.befehle().is_empty() {
+            textln!("   (keine gespeicherten Befehle)");
+            return;
+        }
+        for (i, befehl) in bibliothek.befehle().iter().enumerate() {
+            textln!("{:3}. {}  [{}]", i + 1, befehl.name, befehl.gespeichert);
+            textln!("     {}", befehl.befehl);
+        }
         
-        if index < csv_data.raw_data.len() {
-            let (first_cols, second_cols, _) = &csv_data.raw_data[index];
-            
-            if first_cols.contains(&first.to_string()) && second_cols.contains(&second.to_string()) {
-                count += 1;
+        let auswahl = Text::new("Nummer oder Name (Enter zum Beenden):").prompt().unwrap_or_default();
+        let auswahl = auswahl.trim();
+        if auswahl.is_empty() {
+            return;
+        }
+        
+        let eintrag = match auswahl.parse::<usize>() {
+            Ok(nummer) => nummer.checked_sub(1).and_then(|i| bibliothek.befehle().get(i)),
+            Err(_) => bibliothek.finden(auswahl),
+        };
+        let Some(eintrag) = eintrag.cloned() else {
+            textln!("⚠️  Kein gespeicherter Befehl '{}'", auswahl);
+            continue;
+        };
+        
+        let aktion = Text::new("(a)usführen, (l)öschen oder Enter für zurück:").prompt().unwrap_or_default();
+        match aktion.trim().to_lowercase().chars().next() {
+            Some('a') => {
+                execute_command(&eintrag.befehl, &eintrag.programm, &eintrag.args);
+                return;
+            }
+            Some('l') => {
+                bibliothek.entfernen(&eintrag.name);
+                match bibliothek.speichern(&path) {
+                    Ok(()) => textln!("🗑️  '{}' gelöscht", eintrag.name),
+                    Err(err) => textln!("⚠️  {:#}", err),
+                }
             }
+            _ => {}
+        }
+    }
+}
+
+// Führt rpnn nach Rückfrage direkt aus, ohne Shell: Namen mit Leerzeichen, Anführungszeichen
+// oder `;` werden unverändert als einzelne Argumente übergeben. Die Ausgabe wird aufgefangen,
+// seitenweise angezeigt und kann zusammen mit dem Befehl gespeichert werden.
+fn execute_command(command: &str, programm: &str, args: &[String]) {
+    use std::io;
+    
+    textln!("Auszuführendes Programm: {}", programm);
+    for arg in args {
+        textln!("  Argument: {}", arg);
+    }
+    
+    text!("Jetzt ausführen? (j/N): ");
+    flush_text();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap_or(0);
+    if !input.trim().to_lowercase().starts_with('j') {
+        textln!("ℹ️  Nicht ausgeführt. Befehl kann manuell ausgeführt werden.");
+        return;
+    }
+    
+    textln!("⏳ {} läuft...", programm);
+    let ausfuehrung = match kommando::ausfuehren(programm, args) {
+        Ok(ausfuehrung) => ausfuehrung,
+        Err(err) => {
+            textln!("⚠️  {:#}", err);
+            textln!("   Ist '{}' installiert und im PATH? Befehl kann manuell ausgeführt werden.", programm);
+            return;
+        }
+    };
+    
+    show_paged("📤 AUSGABE (stdout)", &ausfuehrung.stdout);
+    if !ausfuehrung.stderr.is_empty() {
+        show_paged("⚠️  FEHLERAUSGABE (stderr)", &ausfuehrung.stderr);
+    }
+    
+    let symbol = if ausfuehrung.status.success() { "✅" } else { "⚠️ " };
+    textln!("\n{} {}: {}", symbol, programm, ausfuehrung.zusammenfassung());
+    
+    offer_save_output(command, &ausfuehrung);
+}
+
+// Zeigt einen Text seitenweise an; Enter blättert weiter, 'q' bricht ab
+fn show_paged(titel: &str, text: &str) {
+    use std::io;
+    
+    const SEITE: usize = 20;
+    
+    textln!("\n━━━━ {} ━━━━", titel);
+    if text.is_empty() {
+        textln!("(leer)");
+        return;
+    }
+    
+    let zeilen: Vec<&str> = text.lines().collect();
+    for (seite, block) in zeilen.chunks(SEITE).enumerate() {
+        for zeile in block {
+            textln!("{}", zeile);
+        }
+        
+        let gezeigt = (seite + 1) * SEITE;
+        if gezeigt >= zeilen.len() {
+            break;
         }
+        
+        text!("-- {} von {} Zeilen, Enter für mehr, q zum Beenden -- ", gezeigt, zeilen.len());
+        flush_text();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap_or(0);
+        if input.trim().eq_ignore_ascii_case("q") {
+            textln!("... {} weitere Zeilen ausgelassen", zeilen.len() - gezeigt);
+            break;
+        }
+    }
+}
+
+// Speichert auf Wunsch Befehl, Status und Ausgabe in eine Textdatei
+fn offer_save_output(command: &str, ausfuehrung: &kommando::Ausfuehrung) {
+    let speichern = Text::new("Ausgabe in Datei speichern? (j/N)")
+        .with_default("n")
+        .prompt()
+        .unwrap_or_default();
+    if !speichern.to_lowercase().starts_with('j') {
+        return;
     }
     
-    count
+    let filename = match Text::new("Dateiname:").with_default("rpnn_ausgabe.txt").prompt() {
+        Ok(filename) => filename,
+        Err(_) => return,
+    };
+    
+    match std::fs::write(&filename, ausfuehrung.protokoll(command, &zeitstempel())) {
+        Ok(()) => textln!("✅ Ausgabe gespeichert in: {}", filename),
+        Err(e) => textln!("⚠️  Fehler beim Speichern: {}", e),
+    }
+}
+
+// Zählt passende Zeilen
+fn count_matching_rows(first: &str, second: &str, zeilen_numbers: &[i32], csv_data: &CsvData) -> i32 {
+    zeilen_numbers
+        .iter()
+        .filter(|&&row_num| csv_data.row_matches_pair(row_num, first, second))
+        .count() as i32
 }
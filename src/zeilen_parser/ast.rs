@@ -0,0 +1,525 @@
+// zeilen_parser/ast.rs - Syntaxbaum und Parser für Zeilenangaben
+//
+// Grammatik (Leerzeichen zwischen Tokens sind erlaubt):
+//
+//   angabe     := segment? ( ',' segment? )*          mindestens ein nicht-leeres Segment
+//   segment    := '!'? ausdruck
+//   ausdruck   := einfach ( ( '&' | '\' ) einfach )*
+//   einfach    := 'v' basis | basis | bruchbereich
+//   basis      := generator | bereich
+//   generator  := '(' ganzzahl ( ',' ganzzahl )* ')'   ebenso mit [ ] und { }
+//   bereich    := ( '-' ZAHL | ganzzahl ( '-' ganzzahl? )? ) ( '+' ZAHL )*
+//   bruchbereich := bruch ( '-' ZAHL '/' ZAHL )? ( '+' ZAHL '/' ZAHL )*
+//   bruch      := '-'? ZAHL '/' ZAHL
+//   ganzzahl   := '-'? ZAHL
+//
+// Bedeutung von `+` (wie in rpnn): jede Zahl n des Bereichs wählt zusätzlich die Zeilen
+// n-k und n+k für jedes k der Kette, z.B. `5+2` = 3,5,7 und `3-4+1` = 2,3,4,5.
+// Ein absteigender Bereich `8-3` ist derselbe Bereich wie `3-8`.
+//
+// Offene Bereiche: `-5` allein reicht von der ersten Zeile bis Zeile 5 (= `1-5`), `5-` von
+// Zeile 5 bis zur letzten. Als Bereichsgrenze zählt eine negative Zahl vom Tabellenende:
+// `3--2` reicht von Zeile 3 bis zur vorletzten, `-5-` sind die letzten fünf Zeilen und
+// `-1-` ist die letzte. In Generatoren ist `-1` die letzte Zeile, z.B. `(1,-1)`.
+//
+// Bedeutung von `v` (wie in rpnn): alle Vielfachen der Basiszahlen bis zum Tabellenende,
+// z.B. `v5` = 5,10,15,… und `v2-3` = 2,3,4,6,8,9,…; `+k` gilt dann um jedes Vielfache.
+// Nach `v` sind nur positive Zahlen erlaubt.
+//
+// Bedeutung von Brüchen: p/q ist die Position p/q der Tabellenlänge, aufgerundet auf eine
+// Zeile; ein negativer Bruch zählt vom Tabellenende. Bei 20 Zeilen ist `1/2` = 10,
+// `1/4-1/2` = 5-10 und `-1/4` = 16; `1/100` und `-2/1` lägen vor der ersten Zeile und sind Fehler. `+k/m` wirkt wie `+` bei Ganzzahlen, mit
+// k/m der Tabellenlänge als Abstand: `1/2+1/10` = 8,10,12.
+//
+// Mengenoperationen: `a & b` sind die Zeilen, die in a und b liegen, `a \ b` die Zeilen aus a
+// ohne die aus b; mehrere Operatoren werden von links nach rechts ausgewertet. Ein Segment
+// mit `!` nimmt seine Zeilen aus der gesamten Auswahl heraus, unabhängig von seiner
+// Position: `1-50,!13,!20-25` = `1-50 \ 13 \ 20-25` = 1-12,14-19,26-50. Besteht die Angabe
+// nur aus `!`-Segmenten, wird von allen Zeilen der Tabelle ausgegangen.
+use std::fmt;
+use super::{FehlerArt, ParseFehler};
+use super::token::{Token, TokenArt, tokenize};
+
+/// Eine vollständige Zeilenangabe: die nicht-leeren, kommagetrennten Segmente
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zeilenangabe {
+    pub segmente: Vec<Segment>,
+}
+
+/// Ein Segment zwischen zwei Kommas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Einzelne Zahl oder Bereich, z.B. `3`, `3-8`, `3-8+2+5`
+    Bereich(Bereich),
+    /// Generatorliste, z.B. `(1,3,5)` oder `[2,4]`
+    Generator(Vec<i64>),
+    /// `v` vor einem Bereich oder Generator: alle Vielfachen, z.B. `v5`, `v2-5`, `v(3,7)`
+    Vielfache(Box<Segment>),
+    /// Bruch oder Bruchbereich, z.B. `1/2`, `1/2-3/4`, `1/2+1/3`
+    Bruch(Bruchbereich),
+    /// Schnittmenge, z.B. `1-100 & v2`
+    Schnitt(Box<Segment>, Box<Segment>),
+    /// Differenz, z.B. `1-50 \ 13`
+    Differenz(Box<Segment>, Box<Segment>),
+    /// `!` vor einem Segment: seine Zeilen werden aus der gesamten Auswahl entfernt, z.B. `!13`
+    Ohne(Box<Segment>),
+}
+
+/// Ganzzahliger Bereich; `ende` fehlt bei einer einzelnen Zahl, `start` fehlt bei einem
+/// offenen Anfang (`-5` = Zeilen 1 bis 5).
+/// Jedes `k` aus `plus` wählt um jede Zahl n zusätzlich n-k und n+k
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bereich {
+    pub start: Option<i64>,
+    pub ende: Option<Bereichsende>,
+    pub plus: Vec<u64>,
+}
+
+/// Ende eines Bereichs: eine Zahl (negativ = vom Tabellenende) oder offen bis zur letzten Zeile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bereichsende {
+    Zahl(i64),
+    Offen,
+}
+
+/// Ein Bruch mit Vorzeichen im Zähler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bruch {
+    pub zaehler: i64,
+    pub nenner: u64,
+}
+
+impl Bruch {
+    /// Exakt gekürzter Bruch
+    pub fn gekuerzt(self) -> Self {
+        let teiler = ggt(self.zaehler.unsigned_abs(), self.nenner).max(1);
+        Self {
+            zaehler: self.zaehler / teiler as i64,
+            nenner: self.nenner / teiler,
+        }
+    }
+    
+    /// Anzahl Zeilen, die |p/q| der Tabelle entspricht (aufgerundet);
+    /// `None`, wenn das weniger als eine Zeile ist (z.B. `0/5` oder `1/1000` bei 379 Zeilen)
+    pub fn zeilen_anteil(self, max_zeile: i32) -> Option<i64> {
+        let max = i128::from(max_zeile.max(0));
+        let nenner = i128::from(self.nenner);
+        let produkt = i128::from(self.zaehler.unsigned_abs()) * max;
+        if produkt < nenner {
+            return None;
+        }
+        
+        // Ganzzahlige Rechnung, damit z.B. 1/3 von 30 exakt 10 ergibt
+        let anteil = (produkt + nenner - 1) / nenner;
+        Some(anteil.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64)
+    }
+    
+    /// Zeile an der Position p/q der Tabelle (aufgerundet); negative Brüche zählen vom Ende:
+    /// -p/q = Zeile max+1 - (p/q). `None`, wenn die Position vor der ersten Zeile läge
+    /// (z.B. `1/1000` oder `-2/1`)
+    pub fn zeile(self, max_zeile: i32) -> Option<i64> {
+        let position = self.zeilen_anteil(max_zeile)?;
+        if self.zaehler >= 0 {
+            return Some(position);
+        }
+        let zeile = i64::from(max_zeile) + 1 - position;
+        (zeile >= 1).then_some(zeile)
+    }
+}
+
+fn ggt(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Bruch oder Bereich zwischen zwei Brüchen, optional mit `+`-Kette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bruchbereich {
+    pub start: Bruch,
+    pub ende: Option<Bruch>,
+    pub plus: Vec<Bruch>,
+}
+
+impl Zeilenangabe {
+    /// Parst eine komplette Zeilenangabe
+    pub fn parse(text: &str) -> Result<Self, ParseFehler> {
+        Self::parse_segmente(text).map_err(|fehler| fehler.mit_segment(text))
+    }
+    
+    fn parse_segmente(text: &str) -> Result<Self, ParseFehler> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens: &tokens, pos: 0, ende_spalte: text.chars().count() };
+        let mut segmente = Vec::new();
+        
+        loop {
+            // Leere Segmente (z.B. `1,,2` oder ein abschließendes Komma) werden übersprungen
+            if !matches!(parser.peek(), None | Some(TokenArt::Komma)) {
+                segmente.push(parser.segment()?);
+            }
+            
+            match parser.next() {
+                None => break,
+                Some(Token { art: TokenArt::Komma, .. }) => continue,
+                Some(token) => return Err(ParseFehler::new(token.spalte, FehlerArt::KommaOderEndeErwartet)),
+            }
+        }
+        
+        if segmente.is_empty() {
+            return Err(ParseFehler::new(0, FehlerArt::Leer));
+        }
+        
+        Ok(Self { segmente })
+    }
+    
+    /// Alle Segmente bestehen nur aus Brüchen
+    pub fn ist_bruch_angabe(&self) -> bool {
+        self.segmente.iter().all(|s| s.ist_bruch())
+    }
+    
+    /// Kein Segment enthält einen Bruch
+    pub fn ist_ganzzahl_angabe(&self) -> bool {
+        !self.segmente.iter().flat_map(|s| s.einfache_segmente()).any(|s| matches!(s, Segment::Bruch(_)))
+    }
+    
+    /// Enthält die Angabe `!`, `&` oder `\`?
+    pub fn hat_mengenoperationen(&self) -> bool {
+        self.segmente
+            .iter()
+            .any(|s| matches!(s, Segment::Schnitt(..) | Segment::Differenz(..) | Segment::Ohne(_)))
+    }
+    
+    /// Die `v`-Segmente der Angabe
+    pub fn vielfache(&self) -> impl Iterator<Item = &Segment> {
+        self.segmente.iter().filter(|s| matches!(s, Segment::Vielfache(_)))
+    }
+    
+    /// Die Segmente, die rpnn selbst auswertet und die im Befehl erhalten bleiben: `v` und Brüche
+    pub fn rpnn_segmente(&self) -> impl Iterator<Item = &Segment> {
+        self.segmente.iter().filter(|s| matches!(s, Segment::Vielfache(_) | Segment::Bruch(_)))
+    }
+}
+
+impl Segment {
+    /// Hängt das Segment von der Tabellenlänge ab oder ist es offen (negative Zahl, offener
+    /// Anfang oder offenes Ende, Bruch, `v`)?
+    pub fn ist_relativ(&self) -> bool {
+        match self {
+            Segment::Bereich(bereich) => {
+                bereich.start.is_none_or(|start| start < 0)
+                    || matches!(bereich.ende, Some(Bereichsende::Offen))
+                    || matches!(bereich.ende, Some(Bereichsende::Zahl(ende)) if ende < 0)
+            }
+            Segment::Generator(zahlen) => zahlen.iter().any(|&z| z < 0),
+            // Vielfache reichen immer bis zum Tabellenende
+            Segment::Vielfache(_) => true,
+            Segment::Bruch(_) => true,
+            _ => self.einfache_segmente().iter().any(|s| s.ist_relativ()),
+        }
+    }
+    
+    /// Besteht das Segment nur aus Brüchen?
+    pub fn ist_bruch(&self) -> bool {
+        self.einfache_segmente().iter().all(|s| matches!(s, Segment::Bruch(_)))
+    }
+    
+    /// Die Segmente ohne Mengenoperationen, aus denen dieses Segment besteht
+    pub fn einfache_segmente(&self) -> Vec<&Segment> {
+        match self {
+            Segment::Schnitt(links, rechts) | Segment::Differenz(links, rechts) => {
+                let mut segmente = links.einfache_segmente();
+                segmente.extend(rechts.einfache_segmente());
+                segmente
+            }
+            Segment::Ohne(segment) => segment.einfache_segmente(),
+            _ => vec![self],
+        }
+    }
+}
+
+// Kanonische Schreibweise, z.B. für den generierten Befehl
+impl fmt::Display for Zeilenangabe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segmente: Vec<String> = self.segmente.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", segmente.join(","))
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Bereich(bereich) => write!(f, "{}", bereich),
+            Segment::Generator(zahlen) => {
+                let zahlen: Vec<String> = zahlen.iter().map(|z| z.to_string()).collect();
+                write!(f, "({})", zahlen.join(","))
+            }
+            Segment::Vielfache(basis) => write!(f, "v{}", basis),
+            Segment::Bruch(bruchbereich) => write!(f, "{}", bruchbereich),
+            Segment::Schnitt(links, rechts) => write!(f, "{}&{}", links, rechts),
+            Segment::Differenz(links, rechts) => write!(f, "{}\\{}", links, rechts),
+            Segment::Ohne(segment) => write!(f, "!{}", segment),
+        }
+    }
+}
+
+impl fmt::Display for Bereich {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+        match self.ende {
+            Some(Bereichsende::Zahl(ende)) => write!(f, "-{}", ende)?,
+            Some(Bereichsende::Offen) => write!(f, "-")?,
+            None => {}
+        }
+        for k in &self.plus {
+            write!(f, "+{}", k)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Bruch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.zaehler, self.nenner)
+    }
+}
+
+impl fmt::Display for Bruchbereich {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        if let Some(ende) = &self.ende {
+            write!(f, "-{}", ende)?;
+        }
+        for bruch in &self.plus {
+            write!(f, "+{}", bruch)?;
+        }
+        Ok(())
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    // Spalte hinter dem letzten Zeichen (für Fehler am Eingabeende)
+    ende_spalte: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<TokenArt> {
+        self.tokens.get(self.pos).map(|t| t.art)
+    }
+    
+    fn peek_nach(&self, abstand: usize) -> Option<TokenArt> {
+        self.tokens.get(self.pos + abstand).map(|t| t.art)
+    }
+    
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+    
+    fn spalte(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.ende_spalte, |t| t.spalte)
+    }
+    
+    fn fehler(&self, art: FehlerArt) -> ParseFehler {
+        ParseFehler::new(self.spalte(), art)
+    }
+    
+    fn erwarte(&mut self, token: TokenArt, art: FehlerArt) -> Result<(), ParseFehler> {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.fehler(art))
+        }
+    }
+    
+    fn zahl(&mut self) -> Result<u64, ParseFehler> {
+        match self.peek() {
+            Some(TokenArt::Zahl(zahl)) => {
+                self.pos += 1;
+                Ok(zahl)
+            }
+            _ => Err(self.fehler(FehlerArt::ZahlErwartet)),
+        }
+    }
+    
+    fn ganzzahl(&mut self) -> Result<i64, ParseFehler> {
+        let negativ = self.peek() == Some(TokenArt::Minus);
+        if negativ {
+            self.pos += 1;
+        }
+        
+        let zahl = self.zahl_i64()?;
+        Ok(if negativ { -zahl } else { zahl })
+    }
+    
+    // Vorzeichenlose Zahl, die noch in i64 passt
+    fn zahl_i64(&mut self) -> Result<i64, ParseFehler> {
+        let spalte = self.spalte();
+        i64::try_from(self.zahl()?).map_err(|_| ParseFehler::new(spalte, FehlerArt::ZahlZuGross))
+    }
+    
+    fn segment(&mut self) -> Result<Segment, ParseFehler> {
+        if self.peek() == Some(TokenArt::Ausrufezeichen) {
+            self.pos += 1;
+            return Ok(Segment::Ohne(Box::new(self.ausdruck()?)));
+        }
+        
+        self.ausdruck()
+    }
+    
+    // Einfache Segmente, verknüpft mit `&` und `\` (linksassoziativ)
+    fn ausdruck(&mut self) -> Result<Segment, ParseFehler> {
+        let mut segment = self.einfach()?;
+        
+        loop {
+            segment = match self.peek() {
+                Some(TokenArt::Und) => {
+                    self.pos += 1;
+                    Segment::Schnitt(Box::new(segment), Box::new(self.einfach()?))
+                }
+                Some(TokenArt::Rueckstrich) => {
+                    self.pos += 1;
+                    Segment::Differenz(Box::new(segment), Box::new(self.einfach()?))
+                }
+                _ => return Ok(segment),
+            };
+        }
+    }
+    
+    fn einfach(&mut self) -> Result<Segment, ParseFehler> {
+        if self.peek() == Some(TokenArt::V) {
+            self.pos += 1;
+            return self.vielfache();
+        }
+        
+        if let Some(TokenArt::KlammerAuf(klammer)) = self.peek() {
+            self.pos += 1;
+            return Ok(Segment::Generator(self.generator(klammer)?));
+        }
+        
+        // Ein Bruch erkennt sich am '/' nach der (ggf. negativen) ersten Zahl
+        let abstand = if self.peek() == Some(TokenArt::Minus) { 2 } else { 1 };
+        if self.peek_nach(abstand) == Some(TokenArt::Schraegstrich) {
+            return Ok(Segment::Bruch(self.bruchbereich()?));
+        }
+        
+        Ok(Segment::Bereich(self.bereich()?))
+    }
+    
+    // Basis nach `v`: Generator oder Bereich aus positiven Zahlen
+    fn vielfache(&mut self) -> Result<Segment, ParseFehler> {
+        let spalte = self.spalte();
+        let basis = match self.peek() {
+            Some(TokenArt::KlammerAuf(klammer)) => {
+                self.pos += 1;
+                Segment::Generator(self.generator(klammer)?)
+            }
+            Some(TokenArt::Zahl(_)) if self.peek_nach(1) == Some(TokenArt::Schraegstrich) => {
+                return Err(self.fehler(FehlerArt::VielfacheVorBruch));
+            }
+            Some(TokenArt::Zahl(_)) => Segment::Bereich(self.bereich()?),
+            _ => return Err(self.fehler(FehlerArt::VielfacheOhneBasis)),
+        };
+        
+        let positiv = match &basis {
+            Segment::Generator(zahlen) => zahlen.iter().all(|&z| z >= 1),
+            Segment::Bereich(bereich) => {
+                bereich.start.is_some_and(|start| start >= 1) && !matches!(bereich.ende, Some(Bereichsende::Zahl(ende)) if ende < 1)
+            }
+            _ => true,
+        };
+        if !positiv {
+            return Err(ParseFehler::new(spalte, FehlerArt::VielfacheNichtPositiv));
+        }
+        
+        Ok(Segment::Vielfache(Box::new(basis)))
+    }
+    
+    fn generator(&mut self, klammer: char) -> Result<Vec<i64>, ParseFehler> {
+        let schliessend = match klammer {
+            '(' => ')',
+            '[' => ']',
+            _ => '}',
+        };
+        
+        let mut zahlen = vec![self.ganzzahl()?];
+        while self.peek() == Some(TokenArt::Komma) {
+            self.pos += 1;
+            zahlen.push(self.ganzzahl()?);
+        }
+        
+        self.erwarte(TokenArt::KlammerZu(schliessend), FehlerArt::KlammerErwartet(schliessend))?;
+        Ok(zahlen)
+    }
+    
+    fn bereich(&mut self) -> Result<Bereich, ParseFehler> {
+        // `-5` ohne weiteres `-` ist ein offener Anfang; `-5-` und `-5--1` zählen vom Ende
+        let offener_anfang = self.peek() == Some(TokenArt::Minus)
+            && matches!(self.peek_nach(1), Some(TokenArt::Zahl(_)))
+            && self.peek_nach(2) != Some(TokenArt::Minus);
+        let start = if offener_anfang { None } else { Some(self.ganzzahl()?) };
+        
+        let ende = if offener_anfang {
+            self.pos += 1;
+            Some(Bereichsende::Zahl(self.zahl_i64()?))
+        } else if self.peek() == Some(TokenArt::Minus) {
+            self.pos += 1;
+            match self.peek() {
+                Some(TokenArt::Zahl(_) | TokenArt::Minus) => Some(Bereichsende::Zahl(self.ganzzahl()?)),
+                _ => Some(Bereichsende::Offen),
+            }
+        } else {
+            None
+        };
+        
+        let mut plus = Vec::new();
+        while self.peek() == Some(TokenArt::Plus) {
+            self.pos += 1;
+            plus.push(self.zahl()?);
+        }
+        
+        Ok(Bereich { start, ende, plus })
+    }
+    
+    fn bruch(&mut self, mit_vorzeichen: bool) -> Result<Bruch, ParseFehler> {
+        let zaehler = if mit_vorzeichen {
+            self.ganzzahl()?
+        } else {
+            self.zahl_i64()?
+        };
+        
+        self.erwarte(TokenArt::Schraegstrich, FehlerArt::SchraegstrichErwartet)?;
+        let spalte = self.spalte();
+        let nenner = self.zahl()?;
+        if nenner == 0 {
+            return Err(ParseFehler::new(spalte, FehlerArt::NennerNull));
+        }
+        
+        Ok(Bruch { zaehler, nenner })
+    }
+    
+    fn bruchbereich(&mut self) -> Result<Bruchbereich, ParseFehler> {
+        let start = self.bruch(true)?;
+        
+        let ende = if self.peek() == Some(TokenArt::Minus) {
+            self.pos += 1;
+            Some(self.bruch(false)?)
+        } else {
+            None
+        };
+        
+        let mut plus = Vec::new();
+        while self.peek() == Some(TokenArt::Plus) {
+            self.pos += 1;
+            plus.push(self.bruch(false)?);
+        }
+        
+        Ok(Bruchbereich { start, ende, plus })
+    }
+}
@@ -0,0 +1,89 @@
+// zeilen_parser/fehler.rs - Strukturierte Fehler beim Parsen von Zeilenangaben
+use std::fmt;
+
+/// Was an der Fehlerstelle nicht stimmt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FehlerArt {
+    /// Eingabe ohne ein einziges Segment
+    Leer,
+    /// `.` in einer Zahl, z.B. `1.5`
+    Dezimalpunkt,
+    UnerwartetesZeichen(char),
+    ZahlZuGross,
+    ZahlErwartet,
+    KommaOderEndeErwartet,
+    /// Fehlende schließende Klammer eines Generators
+    KlammerErwartet(char),
+    SchraegstrichErwartet,
+    NennerNull,
+    /// `v` vor einem Bruch, z.B. `v1/2`
+    VielfacheVorBruch,
+    /// `v` ohne Zahl oder Generator dahinter
+    VielfacheOhneBasis,
+    /// `v` mit 0 oder negativer Basis, z.B. `v0`
+    VielfacheNichtPositiv,
+}
+
+impl fmt::Display for FehlerArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FehlerArt::Leer => write!(f, "leere Zeilenangabe"),
+            FehlerArt::Dezimalpunkt => write!(f, "Dezimalpunkt nicht erlaubt"),
+            FehlerArt::UnerwartetesZeichen(c) => write!(f, "unerwartetes Zeichen '{}'", c),
+            FehlerArt::ZahlZuGross => write!(f, "Zahl ist zu groß"),
+            FehlerArt::ZahlErwartet => write!(f, "Zahl erwartet"),
+            FehlerArt::KommaOderEndeErwartet => write!(f, "',' oder Ende der Eingabe erwartet"),
+            FehlerArt::KlammerErwartet(klammer) => write!(f, "'{}' erwartet", klammer),
+            FehlerArt::SchraegstrichErwartet => write!(f, "'/' erwartet"),
+            FehlerArt::NennerNull => write!(f, "Nenner darf nicht 0 sein"),
+            FehlerArt::VielfacheVorBruch => write!(f, "'v' ist vor Brüchen nicht erlaubt"),
+            FehlerArt::VielfacheOhneBasis => write!(f, "nach 'v' ist eine positive Zahl oder ein Generator erwartet"),
+            FehlerArt::VielfacheNichtPositiv => write!(f, "Vielfache ('v') brauchen Zahlen ab 1"),
+        }
+    }
+}
+
+/// Fehler beim Parsen einer Zeilenangabe: Art, betroffenes Segment (1-basiert, gezählt an den
+/// Kommas auf oberster Ebene) und Spalte (0-basiert, in Zeichen) in der gesamten Eingabe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFehler {
+    pub art: FehlerArt,
+    pub segment: usize,
+    pub spalte: usize,
+}
+
+impl ParseFehler {
+    pub fn new(spalte: usize, art: FehlerArt) -> Self {
+        Self { art, segment: 1, spalte }
+    }
+    
+    // Bestimmt das Segment der Fehlerstelle; Kommas innerhalb von Generatoren zählen nicht
+    pub(crate) fn mit_segment(mut self, eingabe: &str) -> Self {
+        let mut tiefe = 0usize;
+        self.segment = 1;
+        
+        for c in eingabe.chars().take(self.spalte) {
+            match c {
+                '(' | '[' | '{' => tiefe += 1,
+                ')' | ']' | '}' => tiefe = tiefe.saturating_sub(1),
+                ',' if tiefe == 0 => self.segment += 1,
+                _ => {}
+            }
+        }
+        
+        self
+    }
+    
+    /// Zeigt die Eingabe mit einem `^` unter der Fehlerstelle und der Meldung darunter
+    pub fn anzeigen(&self, eingabe: &str) -> String {
+        format!("{}\n{}^ {}", eingabe, " ".repeat(self.spalte), self)
+    }
+}
+
+impl fmt::Display for ParseFehler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Segment {}, Spalte {}: {}", self.segment, self.spalte, self.art)
+    }
+}
+
+impl std::error::Error for ParseFehler {}
@@ -0,0 +1,358 @@
+// zeilen_parser/mod.rs - Tokenizer, Parser und Expansion von Zeilenangaben
+use anyhow::{Result, bail};
+
+pub mod ast;
+pub mod fehler;
+pub mod token;
+
+pub use ast::{Bereich, Bereichsende, Bruch, Bruchbereich, Segment, Zeilenangabe};
+pub use fehler::{FehlerArt, ParseFehler};
+
+use crate::zeilen_set::ZeilenSet;
+
+/// Expandiert Zeilenangaben in konkrete Zeilennummern
+pub struct ZeilenParser;
+
+impl ZeilenParser {
+    /// Expandiert eine Zeilenangabe (z.B. `1-5,v7,(2,4),1/2`) in sortierte Zeilennummern ohne Duplikate;
+    /// `max_zeile` ist die Tabellenlänge, auf die sich Vielfache, Brüche und negative Zahlen beziehen;
+    /// Zeilen außerhalb von `1..=max_zeile` fallen weg
+    pub fn parse_to_numbers(input: &str, max_zeile: i32) -> Result<Vec<i32>> {
+        if input.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        Self::expand(&Zeilenangabe::parse(input)?, max_zeile)
+    }
+    
+    /// Expandiert eine geparste Zeilenangabe; Zeilen außerhalb von `1..=max_zeile` fallen weg
+    pub fn expand(angabe: &Zeilenangabe, max_zeile: i32) -> Result<Vec<i32>> {
+        Ok(Self::zeilen_set(angabe, max_zeile)?.zeilen(max_zeile))
+    }
+    
+    /// Die ausgewählten Zeilen als Menge von Intervallen, noch nicht auf die Tabelle begrenzt;
+    /// so kostet auch `1-2000000000` nur ein Intervall
+    pub fn zeilen_set(angabe: &Zeilenangabe, max_zeile: i32) -> Result<ZeilenSet> {
+        let mut bereiche = Vec::new();
+        let mut ausgeschlossen = Vec::new();
+        
+        for segment in &angabe.segmente {
+            match segment {
+                Segment::Ohne(segment) => expand_segment(segment, max_zeile, &mut ausgeschlossen)?,
+                _ => expand_segment(segment, max_zeile, &mut bereiche)?,
+            }
+        }
+        
+        // Nur `!`-Segmente: von allen Zeilen der Tabelle ausgehen
+        let nur_ausschluesse = !angabe.segmente.is_empty() && angabe.segmente.iter().all(|s| matches!(s, Segment::Ohne(_)));
+        let auswahl = if nur_ausschluesse {
+            ZeilenSet::alle(max_zeile)
+        } else {
+            ZeilenSet::aus_bereichen(bereiche)
+        };
+        
+        Ok(auswahl.differenz(&ZeilenSet::aus_bereichen(ausgeschlossen)))
+    }
+    
+    /// Die relativen Segmente der Angabe (negative Zahlen, offene Enden, Brüche, `v`)
+    /// mit den Zeilen der Tabelle, zu denen sie bei `max_zeile` Zeilen aufgelöst werden
+    pub fn aufloesungen(angabe: &Zeilenangabe, max_zeile: i32) -> Result<Vec<(&Segment, ZeilenSet)>> {
+        angabe
+            .segmente
+            .iter()
+            .filter(|segment| segment.ist_relativ())
+            .map(|segment| {
+                // Bei `!` werden die ausgeschlossenen Zeilen angezeigt
+                let basis = match segment {
+                    Segment::Ohne(basis) => basis,
+                    _ => segment,
+                };
+                let zeilen = segment_set(basis, max_zeile)?;
+                Ok((segment, zeilen.begrenzt(1, i64::from(max_zeile))))
+            })
+            .collect()
+    }
+    
+    /// Liest die Brüche einer Bruchangabe als (Zähler, Nenner)-Paare
+    pub fn parse_bruch_to_numbers(input: &str) -> Result<Vec<(i32, i32)>> {
+        let angabe = Zeilenangabe::parse(input)?;
+        let mut brueche = Vec::new();
+        
+        for segment in &angabe.segmente {
+            if let Segment::Bruch(bruchbereich) = segment {
+                let alle = std::iter::once(&bruchbereich.start)
+                    .chain(&bruchbereich.ende)
+                    .chain(&bruchbereich.plus);
+                for bruch in alle {
+                    brueche.push((to_i32(bruch.zaehler)?, to_i32(bruch.nenner as i64)?));
+                }
+            }
+        }
+        
+        Ok(brueche)
+    }
+}
+
+fn expand_segment(segment: &Segment, max_zeile: i32, bereiche: &mut Vec<(i64, i64)>) -> Result<()> {
+    let max = i64::from(max_zeile);
+    
+    match segment {
+        Segment::Bereich(bereich) => {
+            let (start, ende) = bereich_grenzen(bereich, max_zeile);
+            push_mit_plus(start, ende, &bereich.plus, bereiche);
+        }
+        Segment::Generator(zahlen) => {
+            for &zahl in zahlen {
+                let zeile = vom_ende(zahl, max_zeile);
+                bereiche.push((zeile, zeile));
+            }
+        }
+        Segment::Vielfache(basis) => {
+            let (basisbereiche, plus) = match basis.as_ref() {
+                Segment::Bereich(bereich) => (vec![bereich_grenzen(bereich, max_zeile)], bereich.plus.as_slice()),
+                Segment::Generator(zahlen) => (zahlen.iter().map(|&z| (z, z)).collect(), &[][..]),
+                _ => bail!("Ungültige Basis für Vielfache: '{}'", basis),
+            };
+            
+            // Der Parser lässt nach `v` nur Zahlen ab 1 zu; Basen über der Tabellenlänge
+            // haben kein Vielfaches in der Tabelle und werden gar nicht erst durchlaufen
+            for (start, ende) in basisbereiche {
+                for basiszahl in start.max(1)..=ende.min(max) {
+                    for vielfaches in (basiszahl..=max).step_by(basiszahl as usize) {
+                        push_mit_plus(vielfaches, vielfaches, plus, bereiche);
+                    }
+                }
+            }
+        }
+        Segment::Bruch(bruchbereich) => {
+            // Brüche werden zu Zeilen und verhalten sich dann wie ein ganzzahliger Bereich
+            // Was vor die erste Zeile fiele, wird abgelehnt statt still zu Zeile 1 zu werden
+            let zeile = |bruch: Bruch| match bruch.zeile(max_zeile) {
+                Some(zeile) => Ok(zeile),
+                None => bail!("Bruch {} liegt bei {} Zeilen vor der ersten Zeile", bruch, max_zeile),
+            };
+            let start = zeile(bruchbereich.start)?;
+            let ende = bruchbereich.ende.map_or(Ok(start), zeile)?;
+            let plus = bruchbereich
+                .plus
+                .iter()
+                .map(|&k| match k.zeilen_anteil(max_zeile) {
+                    Some(anteil) => Ok(anteil.unsigned_abs()),
+                    None => bail!("Abstand +{} ist bei {} Zeilen kleiner als eine Zeile", k, max_zeile),
+                })
+                .collect::<Result<Vec<u64>>>()?;
+            
+            push_mit_plus(start, ende, &plus, bereiche);
+        }
+        Segment::Schnitt(..) | Segment::Differenz(..) => {
+            bereiche.extend_from_slice(segment_set(segment, max_zeile)?.bereiche());
+        }
+        Segment::Ohne(_) => bail!("'!' ist nur am Anfang eines Segments erlaubt: '{}'", segment),
+    }
+    
+    Ok(())
+}
+
+// Zeilen eines einzelnen Segments; Mengenoperationen werden hier ausgewertet
+fn segment_set(segment: &Segment, max_zeile: i32) -> Result<ZeilenSet> {
+    match segment {
+        Segment::Schnitt(links, rechts) => Ok(segment_set(links, max_zeile)?.schnitt(&segment_set(rechts, max_zeile)?)),
+        Segment::Differenz(links, rechts) => Ok(segment_set(links, max_zeile)?.differenz(&segment_set(rechts, max_zeile)?)),
+        _ => {
+            let mut bereiche = Vec::new();
+            expand_segment(segment, max_zeile, &mut bereiche)?;
+            Ok(ZeilenSet::aus_bereichen(bereiche))
+        }
+    }
+}
+
+// Grenzen eines Bereichs; `8-3` wählt dieselben Zeilen wie `3-8`
+fn bereich_grenzen(bereich: &Bereich, max_zeile: i32) -> (i64, i64) {
+    // Offener Anfang: ab der ersten Zeile
+    let start = bereich.start.map_or(1, |start| vom_ende(start, max_zeile));
+    let ende = match bereich.ende {
+        Some(Bereichsende::Zahl(ende)) => vom_ende(ende, max_zeile),
+        Some(Bereichsende::Offen) => i64::from(max_zeile),
+        None => start,
+    };
+    (start.min(ende), start.max(ende))
+}
+
+// Negative Zahlen zählen vom Tabellenende: -1 ist die letzte Zeile
+fn vom_ende(zahl: i64, max_zeile: i32) -> i64 {
+    if zahl < 0 {
+        (i64::from(max_zeile) + 1).saturating_add(zahl)
+    } else {
+        zahl
+    }
+}
+
+// Fügt start..=ende und für jedes `+k` denselben Bereich um k verschoben nach unten und oben hinzu
+fn push_mit_plus(start: i64, ende: i64, plus: &[u64], bereiche: &mut Vec<(i64, i64)>) {
+    bereiche.push((start, ende));
+    for &k in plus {
+        let k = i64::try_from(k).unwrap_or(i64::MAX);
+        bereiche.push((start.saturating_sub(k), ende.saturating_sub(k)));
+        bereiche.push((start.saturating_add(k), ende.saturating_add(k)));
+    }
+}
+
+fn to_i32(zahl: i64) -> Result<i32> {
+    match i32::try_from(zahl) {
+        Ok(zahl) => Ok(zahl),
+        Err(_) => bail!("Zahl {} liegt außerhalb des gültigen Bereichs", zahl),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_ast() {
+        let angabe = Zeilenangabe::parse("v2-5,-3,3-8+2+5,[1, 3],1/2-3/4").unwrap();
+        assert_eq!(angabe.segmente, vec![
+            Segment::Vielfache(Box::new(Segment::Bereich(Bereich { start: Some(2), ende: Some(Bereichsende::Zahl(5)), plus: vec![] }))),
+            Segment::Bereich(Bereich { start: None, ende: Some(Bereichsende::Zahl(3)), plus: vec![] }),
+            Segment::Bereich(Bereich { start: Some(3), ende: Some(Bereichsende::Zahl(8)), plus: vec![2, 5] }),
+            Segment::Generator(vec![1, 3]),
+            Segment::Bruch(Bruchbereich {
+                start: Bruch { zaehler: 1, nenner: 2 },
+                ende: Some(Bruch { zaehler: 3, nenner: 4 }),
+                plus: vec![],
+            }),
+        ]);
+        
+        assert_eq!(Zeilenangabe::parse("(1,2").unwrap_err().spalte, 4);
+        assert!(Zeilenangabe::parse(",").is_err());
+        assert!(Zeilenangabe::parse("v1/2").is_err());
+        assert!(Zeilenangabe::parse("v0").is_err());
+        assert!(Zeilenangabe::parse("v(2,-1)").is_err());
+        
+        // Kanonische Schreibweise
+        assert_eq!(Zeilenangabe::parse("v 2-5+1, [1, 3],,1/2-3/4").unwrap().to_string(), "v2-5+1,(1,3),1/2-3/4");
+        assert!(Zeilenangabe::parse("1/0").is_err());
+    }
+    
+    #[test]
+    fn test_fehler_mit_segment_und_spalte() {
+        let fehler = Zeilenangabe::parse("1-5,1.5,7").unwrap_err();
+        assert_eq!(fehler, ParseFehler { art: FehlerArt::Dezimalpunkt, segment: 2, spalte: 5 });
+        assert_eq!(fehler.to_string(), "Segment 2, Spalte 5: Dezimalpunkt nicht erlaubt");
+        assert_eq!(fehler.anzeigen("1-5,1.5,7"), "1-5,1.5,7\n     ^ Segment 2, Spalte 5: Dezimalpunkt nicht erlaubt");
+        
+        // Kommas im Generator trennen keine Segmente
+        let fehler = Zeilenangabe::parse("(1,2),3,4-x").unwrap_err();
+        assert_eq!((fehler.segment, fehler.spalte), (3, 10));
+        assert_eq!(fehler.art, FehlerArt::UnerwartetesZeichen('x'));
+        
+        assert_eq!(Zeilenangabe::parse("1,(2,3").unwrap_err().art, FehlerArt::KlammerErwartet(')'));
+        assert_eq!(Zeilenangabe::parse("").unwrap_err().art, FehlerArt::Leer);
+    }
+    
+    #[test]
+    fn test_parse_to_numbers() {
+        assert_eq!(ZeilenParser::parse_to_numbers("3-5,1,(9,7)", 20).unwrap(), vec![1, 3, 4, 5, 7, 9]);
+        assert_eq!(ZeilenParser::parse_to_numbers("", 20).unwrap(), Vec::<i32>::new());
+        
+        // `+k` wählt n-k und n+k zusätzlich, absteigende Bereiche sind nicht leer;
+        // was außerhalb der Tabelle landet, fällt weg
+        assert_eq!(ZeilenParser::parse_to_numbers("5+2", 20).unwrap(), vec![3, 5, 7]);
+        assert_eq!(ZeilenParser::parse_to_numbers("3-4+1", 20).unwrap(), vec![2, 3, 4, 5]);
+        assert_eq!(ZeilenParser::parse_to_numbers("10-12+5+20", 20).unwrap(), vec![5, 6, 7, 10, 11, 12, 15, 16, 17]);
+        assert_eq!(ZeilenParser::parse_to_numbers("8-6", 20).unwrap(), vec![6, 7, 8]);
+        
+        // `v`: Vielfache bis zur Tabellenlänge
+        assert_eq!(ZeilenParser::parse_to_numbers("v5", 20).unwrap(), vec![5, 10, 15, 20]);
+        assert_eq!(ZeilenParser::parse_to_numbers("v4-5", 12).unwrap(), vec![4, 5, 8, 10, 12]);
+        assert_eq!(ZeilenParser::parse_to_numbers("v(6,7)", 14).unwrap(), vec![6, 7, 12, 14]);
+        assert_eq!(ZeilenParser::parse_to_numbers("v6+1", 12).unwrap(), vec![5, 6, 7, 11, 12]);
+    }
+    
+    #[test]
+    fn test_negative_und_offene_bereiche() {
+        // Offene Bereiche: `-5` von der ersten Zeile bis 5, `17-` ab 17 bis zur letzten
+        assert_eq!(ZeilenParser::parse_to_numbers("-5", 20).unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(ZeilenParser::parse_to_numbers("-1", 20).unwrap(), vec![1]);
+        assert_eq!(ZeilenParser::parse_to_numbers("-25", 20).unwrap(), (1..=20).collect::<Vec<_>>());
+        assert_eq!(ZeilenParser::parse_to_numbers("-3+10", 20).unwrap(), vec![1, 2, 3, 11, 12, 13]);
+        assert_eq!(ZeilenParser::parse_to_numbers("17-", 20).unwrap(), vec![17, 18, 19, 20]);
+        
+        // Als Bereichsgrenze oder im Generator zählt eine negative Zahl vom Ende
+        assert_eq!(ZeilenParser::parse_to_numbers("-3-", 20).unwrap(), vec![18, 19, 20]);
+        assert_eq!(ZeilenParser::parse_to_numbers("-1-", 20).unwrap(), vec![20]);
+        assert_eq!(ZeilenParser::parse_to_numbers("18--2", 20).unwrap(), vec![18, 19]);
+        assert_eq!(ZeilenParser::parse_to_numbers("(1,-1),v8-", 20).unwrap(), vec![1, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]);
+        assert_eq!(Zeilenangabe::parse("5-,-3--1,-5,-2+1").unwrap().to_string(), "5-,-3--1,-5,-2+1");
+        assert!(Zeilenangabe::parse("v-2").is_err());
+        
+        let angabe = Zeilenangabe::parse("1-5,-3,-2-,1/2,v8").unwrap();
+        let aufloesungen = ZeilenParser::aufloesungen(&angabe, 20).unwrap();
+        assert_eq!(aufloesungen.iter().map(|(s, z)| (s.to_string(), z.to_string())).collect::<Vec<_>>(), vec![
+            ("-3".to_string(), "1-3".to_string()),
+            ("-2-".to_string(), "19-20".to_string()),
+            ("1/2".to_string(), "10".to_string()),
+            ("v8".to_string(), "8,16".to_string()),
+        ]);
+    }
+    
+    #[test]
+    fn test_riesige_bereiche_bleiben_intervalle() {
+        let angabe = Zeilenangabe::parse("1-2000000000,v1-9000000000000000000,5+100").unwrap();
+        let zeilen = ZeilenParser::zeilen_set(&angabe, 20).unwrap();
+        assert_eq!(zeilen.ausserhalb(1, 20).to_string(), "-95,21-2000000000");
+        assert_eq!(ZeilenParser::expand(&angabe, 20).unwrap(), (1..=20).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn test_mengenoperationen() {
+        assert_eq!(ZeilenParser::parse_to_numbers("1-10,!3,!5-8", 20).unwrap(), vec![1, 2, 4, 9, 10]);
+        assert_eq!(ZeilenParser::parse_to_numbers("!3,1-4", 20).unwrap(), vec![1, 2, 4]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1-10 \\ 3 \\ 5-8", 20).unwrap(), vec![1, 2, 4, 9, 10]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1-10 & v3", 20).unwrap(), vec![3, 6, 9]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1-10 \\ 2 & v2,15", 20).unwrap(), vec![4, 6, 8, 10, 15]);
+        
+        // Nur Ausschlüsse: alle Zeilen der Tabelle außer diesen
+        assert_eq!(ZeilenParser::parse_to_numbers("!2-9,!-1-", 10).unwrap(), vec![1]);
+        
+        let angabe = Zeilenangabe::parse("!1/2, 1-5 & [2,4] \\ 4").unwrap();
+        assert_eq!(angabe.to_string(), "!1/2,1-5&(2,4)\\4");
+        assert!(angabe.hat_mengenoperationen() && !angabe.ist_ganzzahl_angabe() && !angabe.ist_bruch_angabe());
+        assert_eq!(Zeilenangabe::parse("1-5,!").unwrap_err().art, FehlerArt::ZahlErwartet);
+        assert_eq!(Zeilenangabe::parse("1-5&!3").unwrap_err().art, FehlerArt::ZahlErwartet);
+    }
+    
+    #[test]
+    fn test_brueche_waehlen_zeilen() {
+        assert_eq!(Bruch { zaehler: 6, nenner: 8 }.gekuerzt(), Bruch { zaehler: 3, nenner: 4 });
+        assert_eq!(Bruch { zaehler: 1, nenner: 3 }.zeile(30), Some(10));
+        assert_eq!(Bruch { zaehler: 1, nenner: 3 }.zeile(31), Some(11));
+        assert_eq!(Bruch { zaehler: -1, nenner: 4 }.zeile(20), Some(16));
+        assert_eq!(Bruch { zaehler: 1, nenner: 20 }.zeile(20), Some(1));
+        
+        // Weniger als eine Zeile wird nicht still zu Zeile 1 bzw. Abstand 1
+        assert_eq!(Bruch { zaehler: 0, nenner: 5 }.zeile(20), None);
+        assert_eq!(Bruch { zaehler: -1, nenner: 1000 }.zeile(379), None);
+        let fehler = ZeilenParser::parse_to_numbers("1/1000", 379).unwrap_err();
+        assert_eq!(fehler.to_string(), "Bruch 1/1000 liegt bei 379 Zeilen vor der ersten Zeile");
+        let fehler = ZeilenParser::parse_to_numbers("1/2+0/5", 20).unwrap_err();
+        assert_eq!(fehler.to_string(), "Abstand +0/5 ist bei 20 Zeilen kleiner als eine Zeile");
+        assert!(ZeilenParser::parse_to_numbers("1/2+1/1000", 379).is_err());
+        
+        // Negative Brüche über die ganze Tabelle hinaus liegen ebenfalls vor der ersten Zeile
+        assert_eq!(Bruch { zaehler: -1, nenner: 1 }.zeile(379), Some(1));
+        assert_eq!(Bruch { zaehler: -2, nenner: 1 }.zeile(379), None);
+        assert_eq!(Bruch { zaehler: -5, nenner: 1 }.zeile(379), None);
+        let fehler = ZeilenParser::parse_to_numbers("-5/1", 379).unwrap_err();
+        assert_eq!(fehler.to_string(), "Bruch -5/1 liegt bei 379 Zeilen vor der ersten Zeile");
+        
+        assert_eq!(ZeilenParser::parse_to_numbers("1/2", 20).unwrap(), vec![10]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1/4-1/2", 20).unwrap(), vec![5, 6, 7, 8, 9, 10]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1/2-1/4", 20).unwrap(), vec![5, 6, 7, 8, 9, 10]);
+        assert_eq!(ZeilenParser::parse_to_numbers("1/2+1/10,1/1", 20).unwrap(), vec![8, 10, 12, 20]);
+        
+        // Ganzzahlen und Brüche gemischt
+        assert_eq!(ZeilenParser::parse_to_numbers("1-3,1/2,v9", 20).unwrap(), vec![1, 2, 3, 9, 10, 18]);
+        assert_eq!(ZeilenParser::parse_bruch_to_numbers("1/2-3/4,5/6").unwrap(), vec![(1, 2), (3, 4), (5, 6)]);
+    }
+}
@@ -0,0 +1,73 @@
+// zeilen_parser/token.rs - Zerlegt eine Zeilenangabe in Tokens
+use super::{FehlerArt, ParseFehler};
+
+/// Art eines Tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenArt {
+    /// Vorzeichenlose Zahl
+    Zahl(u64),
+    Minus,
+    Plus,
+    Schraegstrich,
+    Komma,
+    /// Präfix `v` bzw. `V`
+    V,
+    /// `!` vor einem Segment (Ausschluss)
+    Ausrufezeichen,
+    /// `&` (Schnittmenge)
+    Und,
+    /// `\` (Differenz)
+    Rueckstrich,
+    /// Öffnende Klammer `(`, `[` oder `{`
+    KlammerAuf(char),
+    /// Schließende Klammer `)`, `]` oder `}`
+    KlammerZu(char),
+}
+
+/// Ein Token mit seiner Spalte (0-basiert, in Zeichen) in der Eingabe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub art: TokenArt,
+    pub spalte: usize,
+}
+
+/// Zerlegt den Text in Tokens; Leerzeichen zwischen Tokens werden übersprungen
+pub fn tokenize(text: &str) -> Result<Vec<Token>, ParseFehler> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().enumerate().peekable();
+    
+    while let Some((spalte, c)) = chars.next() {
+        let art = match c {
+            '0'..='9' => {
+                let mut ziffern = c.to_string();
+                while let Some(&(_, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    ziffern.push(d);
+                    chars.next();
+                }
+                let zahl = ziffern
+                    .parse()
+                    .map_err(|_| ParseFehler::new(spalte, FehlerArt::ZahlZuGross))?;
+                TokenArt::Zahl(zahl)
+            }
+            '-' => TokenArt::Minus,
+            '+' => TokenArt::Plus,
+            '/' => TokenArt::Schraegstrich,
+            ',' => TokenArt::Komma,
+            'v' | 'V' => TokenArt::V,
+            '!' => TokenArt::Ausrufezeichen,
+            '&' => TokenArt::Und,
+            '\\' => TokenArt::Rueckstrich,
+            '(' | '[' | '{' => TokenArt::KlammerAuf(c),
+            ')' | ']' | '}' => TokenArt::KlammerZu(c),
+            '.' => return Err(ParseFehler::new(spalte, FehlerArt::Dezimalpunkt)),
+            c if c.is_whitespace() => continue,
+            c => return Err(ParseFehler::new(spalte, FehlerArt::UnerwartetesZeichen(c))),
+        };
+        tokens.push(Token { art, spalte });
+    }
+    
+    Ok(tokens)
+}
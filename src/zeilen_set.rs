@@ -0,0 +1,229 @@
+// zeilen_set.rs - Zeilenauswahl als Menge geschlossener Intervalle
+//
+// Eine Angabe wie `1-2000000000` wird nicht Zahl für Zahl expandiert, sondern bleibt ein
+// einziges Intervall; erst nach dem Begrenzen auf die Tabelle werden Zeilennummern erzeugt.
+// Die Grenzen sind i64, damit auch Zeilen außerhalb der Tabelle (z.B. durch `+k`) ohne
+// Überlauf darstellbar bleiben und gemeldet werden können.
+use std::fmt;
+
+/// Menge von Zeilennummern als sortierte, disjunkte und nicht aneinandergrenzende Intervalle
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZeilenSet {
+    bereiche: Vec<(i64, i64)>,
+}
+
+impl ZeilenSet {
+    /// Leere Menge
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Alle Zeilen der Tabelle, `1..=max_zeile`
+    pub fn alle(max_zeile: i32) -> Self {
+        let bereiche = if max_zeile >= 1 { vec![(1, i64::from(max_zeile))] } else { Vec::new() };
+        Self { bereiche }
+    }
+    
+    /// Baut die Menge aus beliebigen (auch überlappenden oder absteigenden) Intervallen
+    pub fn aus_bereichen(mut bereiche: Vec<(i64, i64)>) -> Self {
+        for bereich in &mut bereiche {
+            *bereich = (bereich.0.min(bereich.1), bereich.0.max(bereich.1));
+        }
+        bereiche.sort_unstable();
+        
+        let mut zusammengefasst: Vec<(i64, i64)> = Vec::with_capacity(bereiche.len());
+        for (start, ende) in bereiche {
+            match zusammengefasst.last_mut() {
+                // Überlappend oder direkt anschließend: verlängern
+                Some(letzter) if start <= letzter.1.saturating_add(1) => letzter.1 = letzter.1.max(ende),
+                _ => zusammengefasst.push((start, ende)),
+            }
+        }
+        
+        Self { bereiche: zusammengefasst }
+    }
+    
+    /// Die Intervalle in aufsteigender Reihenfolge
+    pub fn bereiche(&self) -> &[(i64, i64)] {
+        &self.bereiche
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.bereiche.is_empty()
+    }
+    
+    /// Anzahl der enthaltenen Zeilen (ohne sie zu erzeugen)
+    pub fn anzahl(&self) -> u64 {
+        self.bereiche.iter().map(|&(start, ende)| ende.abs_diff(start) + 1).sum()
+    }
+    
+    pub fn contains(&self, zeile: i64) -> bool {
+        // Erstes Intervall, dessen Ende nicht vor der Zeile liegt
+        let index = self.bereiche.partition_point(|&(_, ende)| ende < zeile);
+        self.bereiche.get(index).is_some_and(|&(start, _)| start <= zeile)
+    }
+    
+    /// Alle Zeilen, die in einer der beiden Mengen liegen
+    pub fn vereinigung(&self, andere: &ZeilenSet) -> Self {
+        Self::aus_bereichen(self.bereiche.iter().chain(&andere.bereiche).copied().collect())
+    }
+    
+    /// Alle Zeilen, die in beiden Mengen liegen
+    pub fn schnitt(&self, andere: &ZeilenSet) -> Self {
+        let mut bereiche = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        
+        while let (Some(&(a_start, a_ende)), Some(&(b_start, b_ende))) = (self.bereiche.get(i), andere.bereiche.get(j)) {
+            let (start, ende) = (a_start.max(b_start), a_ende.min(b_ende));
+            if start <= ende {
+                bereiche.push((start, ende));
+            }
+            // Das Intervall, das zuerst endet, kann nichts mehr schneiden
+            if a_ende < b_ende {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        
+        Self { bereiche }
+    }
+    
+    /// Alle Zeilen dieser Menge, die nicht in `andere` liegen
+    pub fn differenz(&self, andere: &ZeilenSet) -> Self {
+        let mut bereiche = Vec::new();
+        let mut j = 0;
+        
+        for &(start, ende) in &self.bereiche {
+            // Noch nicht abgedeckter Anfang des Intervalls; None, sobald alles abgedeckt ist
+            let mut rest = Some(start);
+            
+            // Intervalle von `andere`, die vollständig vor diesem liegen, überspringen
+            while andere.bereiche.get(j).is_some_and(|&(_, b_ende)| b_ende < start) {
+                j += 1;
+            }
+            
+            for &(b_start, b_ende) in &andere.bereiche[j..] {
+                let Some(anfang) = rest else { break };
+                if b_start > ende {
+                    break;
+                }
+                if b_start > anfang {
+                    bereiche.push((anfang, b_start - 1));
+                }
+                // b_ende < ende, daher kein Überlauf
+                rest = (b_ende < ende).then(|| anfang.max(b_ende + 1));
+            }
+            
+            if let Some(anfang) = rest {
+                bereiche.push((anfang, ende));
+            }
+        }
+        
+        Self { bereiche }
+    }
+    
+    /// Alle Zeilen der Tabelle (`1..=max_zeile`), die nicht in der Menge liegen
+    pub fn komplement(&self, max_zeile: i32) -> Self {
+        Self::alle(max_zeile).differenz(self)
+    }
+    
+    /// Schnittmenge mit `min..=max`
+    pub fn begrenzt(&self, min: i64, max: i64) -> Self {
+        let bereiche = self
+            .bereiche
+            .iter()
+            .map(|&(start, ende)| (start.max(min), ende.min(max)))
+            .filter(|&(start, ende)| start <= ende)
+            .collect();
+        Self { bereiche }
+    }
+    
+    /// Alles, was außerhalb von `min..=max` liegt
+    pub fn ausserhalb(&self, min: i64, max: i64) -> Self {
+        let mut bereiche = Vec::new();
+        for &(start, ende) in &self.bereiche {
+            if start < min {
+                bereiche.push((start, ende.min(min.saturating_sub(1))));
+            }
+            if ende > max {
+                bereiche.push((start.max(max.saturating_add(1)), ende));
+            }
+        }
+        Self { bereiche }
+    }
+    
+    /// Alle enthaltenen Zeilen aufsteigend; vorher begrenzen, sonst kann das sehr viele sein
+    pub fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        self.bereiche.iter().flat_map(|&(start, ende)| start..=ende)
+    }
+    
+    /// Die Zeilennummern innerhalb der Tabelle (`1..=max_zeile`)
+    pub fn zeilen(&self, max_zeile: i32) -> Vec<i32> {
+        // Nach dem Begrenzen passt jede Zahl in i32 und es sind höchstens max_zeile viele
+        self.begrenzt(1, i64::from(max_zeile)).iter().map(|zeile| zeile as i32).collect()
+    }
+    
+    /// Kompakte Schreibweise, nach `max_bereiche` Intervallen mit `…` abgekürzt
+    pub fn kurz(&self, max_bereiche: usize) -> String {
+        if self.bereiche.len() <= max_bereiche {
+            return self.to_string();
+        }
+        let anfang = Self { bereiche: self.bereiche[..max_bereiche].to_vec() };
+        format!("{},…", anfang)
+    }
+}
+
+impl FromIterator<i32> for ZeilenSet {
+    fn from_iter<I: IntoIterator<Item = i32>>(zeilen: I) -> Self {
+        Self::aus_bereichen(zeilen.into_iter().map(|zeile| (i64::from(zeile), i64::from(zeile))).collect())
+    }
+}
+
+impl fmt::Display for ZeilenSet {
+    // Kompakte Schreibweise wie in Zeilenangaben: `1-5,10`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, &(start, ende)) in self.bereiche.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            if start == ende {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}-{}", start, ende)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_mengenoperationen() {
+        let a: ZeilenSet = [1, 2, 3, 4, 5, 10, 11, 12].into_iter().collect();
+        let b = ZeilenSet::aus_bereichen(vec![(4, 10), (20, 20)]);
+        
+        assert_eq!(a.to_string(), "1-5,10-12");
+        assert_eq!(a.vereinigung(&b).to_string(), "1-12,20");
+        assert_eq!(a.schnitt(&b).to_string(), "4-5,10");
+        assert_eq!(a.differenz(&b).to_string(), "1-3,11-12");
+        assert_eq!(b.differenz(&a).to_string(), "6-9,20");
+        assert_eq!(a.komplement(15).to_string(), "6-9,13-15");
+        assert_eq!(ZeilenSet::new().komplement(3).to_string(), "1-3");
+        
+        assert!(a.contains(11) && !a.contains(7) && !a.contains(0));
+        assert_eq!(a.anzahl(), 8);
+        assert_eq!(a.zeilen(4), vec![1, 2, 3, 4]);
+        assert_eq!(a.kurz(1), "1-5,…");
+        
+        // Große Intervalle und Grenzen werden nicht expandiert
+        let riesig = ZeilenSet::aus_bereichen(vec![(-5, 2_000_000_000)]);
+        assert_eq!(riesig.ausserhalb(1, 20).to_string(), "-5-0,21-2000000000");
+        assert_eq!(riesig.differenz(&ZeilenSet::alle(20)).anzahl(), 2_000_000_000 - 20 + 6);
+        let voll = ZeilenSet::aus_bereichen(vec![(i64::MIN, i64::MAX)]);
+        assert!(voll.differenz(&voll).is_empty());
+    }
+}